@@ -0,0 +1,168 @@
+use crate::config::{Station, parse_date};
+use crate::error::Result;
+use crate::frost_client::{AvailableTimeSeries, FrostClient, PRECIPITATION_TIME_OFFSET};
+use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+
+/// Which days Frost holds a daily precipitation series for, per station.
+///
+/// Built from `observations/availableTimeSeries` for the `PT6H` offset we ingest.
+/// Intervals are half-open `[from, to)` and merged, so they never overlap.
+#[derive(Debug)]
+pub struct Availability {
+    intervals: HashMap<String, Vec<(NaiveDate, NaiveDate)>>,
+    /// Stations whose every series has ended, with the last end date.
+    ended: HashMap<String, NaiveDate>,
+}
+
+impl Availability {
+    /// Clip the series to `from..to` and merge them per station.
+    pub fn from_series(series: &[AvailableTimeSeries], from: NaiveDate, to: NaiveDate) -> Self {
+        let mut raw: HashMap<String, Vec<(NaiveDate, NaiveDate)>> = HashMap::new();
+        let mut open_ended: HashSet<String> = HashSet::new();
+        let mut last_end: HashMap<String, NaiveDate> = HashMap::new();
+
+        for s in series {
+            if s.time_offset
+                .as_deref()
+                .is_some_and(|o| o != PRECIPITATION_TIME_OFFSET)
+            {
+                continue;
+            }
+            let station = s.station_id().to_string();
+            let Some(start) = frost_date(&s.valid_from) else {
+                continue;
+            };
+            let end = s.valid_to.as_deref().and_then(frost_date);
+
+            match end {
+                None => {
+                    open_ended.insert(station.clone());
+                }
+                Some(end) => {
+                    let last = last_end.entry(station.clone()).or_insert(end);
+                    *last = (*last).max(end);
+                }
+            }
+
+            let (start, end) = (start.max(from), end.unwrap_or(to).min(to));
+            if start < end {
+                raw.entry(station).or_default().push((start, end));
+            }
+        }
+
+        let intervals = raw
+            .into_iter()
+            .map(|(station, mut iv)| {
+                iv.sort();
+                let mut merged: Vec<(NaiveDate, NaiveDate)> = Vec::with_capacity(iv.len());
+                for (start, end) in iv {
+                    match merged.last_mut() {
+                        Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                        _ => merged.push((start, end)),
+                    }
+                }
+                (station, merged)
+            })
+            .collect();
+
+        let ended = last_end
+            .into_iter()
+            .filter(|(station, _)| !open_ended.contains(station))
+            .collect();
+
+        Self { intervals, ended }
+    }
+
+    /// True if any series overlaps `from..to` for the station.
+    pub fn has_data(&self, station_id: &str, from: NaiveDate, to: NaiveDate) -> bool {
+        self.intervals
+            .get(station_id)
+            .is_some_and(|iv| iv.iter().any(|&(s, e)| s < to && e > from))
+    }
+
+    /// Sub-periods of `from..to` with no series for the station.
+    pub fn gaps(
+        &self,
+        station_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<(NaiveDate, NaiveDate)> {
+        let mut gaps = Vec::new();
+        let mut cursor = from;
+        for &(start, end) in self.intervals.get(station_id).into_iter().flatten() {
+            if start >= to {
+                break;
+            }
+            if start > cursor {
+                gaps.push((cursor, start));
+            }
+            cursor = cursor.max(end);
+        }
+        if cursor < to {
+            gaps.push((cursor, to));
+        }
+        gaps
+    }
+
+    /// Fraction of days in `from..to` covered by a series.
+    pub fn coverage(&self, station_id: &str, from: NaiveDate, to: NaiveDate) -> f64 {
+        let total_days = (to - from).num_days();
+        if total_days <= 0 {
+            return 0.0;
+        }
+        let missing: i64 = self
+            .gaps(station_id, from, to)
+            .iter()
+            .map(|(s, e)| (*e - *s).num_days())
+            .sum();
+        (total_days - missing) as f64 / total_days as f64
+    }
+
+    /// Date the station's last series ended, if none of them are still running.
+    pub fn ended_on(&self, station_id: &str) -> Option<NaiveDate> {
+        self.ended.get(station_id).copied()
+    }
+}
+
+/// Frost timestamps look like "2015-01-01T00:00:00.000Z"; we only need the date.
+fn frost_date(timestamp: &str) -> Option<NaiveDate> {
+    timestamp.get(..10).and_then(|d| parse_date(d).ok())
+}
+
+/// Query availableTimeSeries for the configured stations and log what won't be fetched:
+/// stations with no series at all, gaps inside the period, and discontinued stations.
+pub fn preflight(
+    frost: &FrostClient,
+    stations: &[&Station],
+    from: &str,
+    to: &str,
+) -> Result<Availability> {
+    let (from_date, to_date) = (parse_date(from)?, parse_date(to)?);
+    let ids: Vec<&str> = stations.iter().map(|s| s.id).collect();
+    let series = frost.available_time_series(&ids, from, to)?;
+    let availability = Availability::from_series(&series, from_date, to_date);
+
+    for station in stations {
+        if let Some(ended) = availability.ended_on(station.id) {
+            warn!(
+                station = station.id,
+                name = station.name,
+                ended = %ended,
+                "Station has been discontinued — consider replacing it (see `stations suggest`)"
+            );
+        }
+
+        if !availability.has_data(station.id, from_date, to_date) {
+            warn!(station = station.id, period = %format_args!("{from}/{to}"), "No time series in period (skipping station)");
+            continue;
+        }
+
+        for (gap_from, gap_to) in availability.gaps(station.id, from_date, to_date) {
+            info!(station = station.id, from = %gap_from, to = %gap_to, "No time series for period");
+        }
+    }
+
+    Ok(availability)
+}
@@ -0,0 +1,80 @@
+use crate::config::{ElArea, Station};
+use crate::error::{FrostCliError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// Station catalogue file, as written by `frost stations suggest` and read by
+/// `frost ingest --stations-file`. Replaces the built-in `STATIONS` list when given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StationCatalogue {
+    /// When and for which period the scores were computed (informational only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    pub stations: Vec<CatalogueStation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogueStation {
+    pub id: String,
+    pub name: String,
+    pub el_area: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    /// Why the station was picked. Absent in hand-written catalogues.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<SelectionMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionMetrics {
+    /// Combined score in 0..1 at the time the station was picked.
+    pub score: f64,
+    /// Fraction of days in the period covered by a Frost time series.
+    pub coverage: f64,
+    /// Station and its time series are still active.
+    pub active: bool,
+    /// Distance to the nearest station picked before it in the same area.
+    pub nearest_selected_km: Option<f64>,
+}
+
+impl StationCatalogue {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| {
+            FrostCliError::config(format!("Invalid station catalogue {}: {e}", path.display()))
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| {
+            FrostCliError::config(format!("Could not serialize station catalogue: {e}"))
+        })?;
+        std::fs::write(path, text + "\n")?;
+        info!(path = %path.display(), stations = self.stations.len(), "Wrote station catalogue");
+        Ok(())
+    }
+
+    /// Convert into `Station`s usable by the ingest pipeline.
+    ///
+    /// The strings are leaked so the result has the same `'static` lifetime as the
+    /// built-in `STATIONS` table; a catalogue is loaded at most once per process.
+    pub fn into_stations(self) -> Result<Vec<&'static Station>> {
+        self.stations
+            .into_iter()
+            .map(|s| {
+                let el_area: ElArea = s.el_area.parse()?;
+                let station = Station {
+                    id: Box::leak(s.id.into_boxed_str()),
+                    name: Box::leak(s.name.into_boxed_str()),
+                    el_area,
+                };
+                Ok(&*Box::leak(Box::new(station)))
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,19 @@
 use crate::error::{FrostCliError, Result};
+use crate::gap_fill::GapFillSettings;
+use crate::outliers::OutlierSettings;
+use crate::validation::{RuleAction, ValidationRules};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Parse a "YYYY-MM-DD" date.
+pub fn parse_date(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| FrostCliError::config(format!("Invalid date: {s}")))
+}
 
 /// Split a date range into yearly chunks: [(from, to), ...]
 /// Dates are "YYYY-MM-DD" strings. Each chunk starts on Jan 1st.
@@ -119,11 +133,10 @@ pub fn stations_for_areas(areas: &[ElArea]) -> Vec<&'static Station> {
     STATIONS.iter().filter(|s| areas.contains(&s.el_area)).collect()
 }
 
-pub fn station_by_id(id: &str) -> Option<&'static Station> {
-    STATIONS.iter().find(|s| s.id == id)
-}
-
 /// Map Norwegian county names (both old and new) to electricity areas.
+/// Coarse fallback for stations without coordinates: counties that straddle area
+/// borders (Telemark, Vestland, Nordland) are assigned wholesale to one area.
+/// Prefer [`crate::geo::el_area_for_point`] whenever a position is known.
 pub fn county_to_el_area(county: &str) -> Option<ElArea> {
     let c = county.to_uppercase();
     // NO1 — Eastern Norway
@@ -153,59 +166,667 @@ pub fn county_to_el_area(county: &str) -> Option<ElArea> {
     None
 }
 
+/// Config file read from the working directory when `--config` isn't given.
+const DEFAULT_CONFIG_FILE: &str = "frost.toml";
+
+/// Default cap on the SQL text of one INSERT statement.
+const DEFAULT_MAX_STATEMENT_BYTES: usize = 4 * 1024 * 1024;
+
+/// The Statement Execution API rejects statements larger than this.
+const STATEMENT_API_LIMIT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default wait for a stopped SQL warehouse to come up.
+const DEFAULT_WAREHOUSE_TIMEOUT_SECS: u64 = 600;
+
+/// Default limit on how long one SQL statement may run before it is cancelled.
+const DEFAULT_STATEMENT_TIMEOUT_SECS: u64 = 3600;
+
+/// Default upper bound for a plausible daily precipitation total, in mm. The
+/// Norwegian record is 229.6 mm (Indre Matre, 1940).
+const DEFAULT_MAX_DAILY_MM: f64 = 300.0;
+
+/// Default robust z-score above which a station-day is a spatial outlier. 3.5 is
+/// Iglewicz and Hoaglin's recommended cut-off for the modified z-score.
+const DEFAULT_OUTLIER_THRESHOLD: f64 = 3.5;
+
+/// Default number of other stations in the area needed to score a station-day.
+const DEFAULT_OUTLIER_MIN_NEIGHBOURS: usize = 3;
+
+/// Default floor for the MAD, in mm. With the threshold above, a station is only
+/// flagged on a day its neighbours are dry once it reports more than about 10 mm.
+const DEFAULT_OUTLIER_MIN_MAD_MM: f64 = 2.0;
+
+/// Default number of neighbour stations with a value needed to fill a missing day.
+const DEFAULT_GAP_FILL_MIN_DONORS: usize = 2;
+
+/// Default number of days both stations must have reported before their ratio is
+/// trusted for gap filling.
+const DEFAULT_GAP_FILL_MIN_OVERLAP_DAYS: usize = 30;
+
+/// A credential that shows up as `***` in `Debug` output and logs.
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"***\"")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(s: String) -> Self {
+        Secret(s)
+    }
+}
+
+/// One layer of settings. `None` means "not set in this layer".
+///
+/// Layers are merged in order defaults < config file < profile < env < CLI flags,
+/// each later layer overriding the fields it sets.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigLayer {
+    pub frost: FrostLayer,
+    pub databricks: DatabricksLayer,
+    pub validation: ValidationLayer,
+    pub outliers: OutliersLayer,
+    pub gap_fill: GapFillLayer,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrostLayer {
+    pub client_id: Option<Secret>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabricksLayer {
+    pub hostname: Option<String>,
+    pub http_path: Option<String>,
+    pub catalog: Option<String>,
+    /// Schema the raw tables are written to (default `raw_frost`).
+    pub schema: Option<String>,
+    /// Precipitation table name within `schema` (default `precipitation`).
+    pub table: Option<String>,
+    /// Unity Catalog volume in `schema` for bulk-load staging files (default `frost_staging`).
+    pub volume: Option<String>,
+    /// Table within `schema` for `ingest --fill-gaps` output (default `<table>_gap_filled`).
+    pub gap_filled_table: Option<String>,
+    pub access_token: Option<Secret>,
+    /// OAuth machine-to-machine credentials of a service principal; used instead of
+    /// `access_token` when set.
+    pub client_id: Option<String>,
+    pub client_secret: Option<Secret>,
+    /// Upper bound on the SQL text of one INSERT statement, in bytes (default 4 MiB).
+    pub max_statement_bytes: Option<usize>,
+    /// How long to wait for the SQL warehouse to reach RUNNING, in seconds (default 600).
+    pub warehouse_timeout_secs: Option<u64>,
+    /// How long one SQL statement may run before it is cancelled, in seconds (default 3600).
+    pub statement_timeout_secs: Option<u64>,
+}
+
+/// Action per validation rule (`reject`, `flag` or `fail`).
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationLayer {
+    /// Precipitation below zero (default `reject`).
+    pub negative: Option<RuleAction>,
+    /// Daily total above `max_daily_mm` (default `flag`).
+    pub implausible_total: Option<RuleAction>,
+    pub max_daily_mm: Option<f64>,
+    /// Same station and date twice in one run; the first row is kept (default `reject`).
+    pub duplicate: Option<RuleAction>,
+    /// Date outside `--from`..`--to` (default `reject`). `flag` isn't allowed: the
+    /// row would be written outside the range a re-run deletes, and pile up.
+    pub out_of_range: Option<RuleAction>,
+}
+
+/// Spatial consistency check against other stations in the same area.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutliersLayer {
+    /// Robust z-score above which a station-day is flagged (default 3.5).
+    pub threshold: Option<f64>,
+    /// Other stations with a value that day needed to score a station (default 3).
+    pub min_neighbours: Option<usize>,
+    /// Lower bound for the median absolute deviation, in mm (default 2).
+    pub min_mad_mm: Option<f64>,
+}
+
+/// Filling missing station-days from neighbouring stations (`ingest --fill-gaps`).
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GapFillLayer {
+    /// Neighbour stations with a value that day needed to fill it (default 2).
+    pub min_donors: Option<usize>,
+    /// Days both stations reported, needed to use a neighbour's ratio (default 30).
+    pub min_overlap_days: Option<usize>,
+}
+
+impl ConfigLayer {
+    fn defaults() -> Self {
+        ConfigLayer {
+            frost: FrostLayer::default(),
+            databricks: DatabricksLayer {
+                catalog: Some("workspace".to_string()),
+                schema: Some("raw_frost".to_string()),
+                table: Some("precipitation".to_string()),
+                volume: Some("frost_staging".to_string()),
+                max_statement_bytes: Some(DEFAULT_MAX_STATEMENT_BYTES),
+                warehouse_timeout_secs: Some(DEFAULT_WAREHOUSE_TIMEOUT_SECS),
+                statement_timeout_secs: Some(DEFAULT_STATEMENT_TIMEOUT_SECS),
+                ..DatabricksLayer::default()
+            },
+            validation: ValidationLayer {
+                negative: Some(RuleAction::Reject),
+                implausible_total: Some(RuleAction::Flag),
+                max_daily_mm: Some(DEFAULT_MAX_DAILY_MM),
+                duplicate: Some(RuleAction::Reject),
+                out_of_range: Some(RuleAction::Reject),
+            },
+            outliers: OutliersLayer {
+                threshold: Some(DEFAULT_OUTLIER_THRESHOLD),
+                min_neighbours: Some(DEFAULT_OUTLIER_MIN_NEIGHBOURS),
+                min_mad_mm: Some(DEFAULT_OUTLIER_MIN_MAD_MM),
+            },
+            gap_fill: GapFillLayer {
+                min_donors: Some(DEFAULT_GAP_FILL_MIN_DONORS),
+                min_overlap_days: Some(DEFAULT_GAP_FILL_MIN_OVERLAP_DAYS),
+            },
+        }
+    }
+
+    fn from_env() -> Result<Self> {
+        let var = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+        Ok(ConfigLayer {
+            frost: FrostLayer {
+                client_id: var("FROST_CLIENT_ID").map(Secret),
+            },
+            databricks: DatabricksLayer {
+                hostname: var("DATABRICKS_HOSTNAME"),
+                http_path: var("DATABRICKS_HTTP_PATH"),
+                catalog: var("DATABRICKS_CATALOG"),
+                // Also read by the dbt source definition, so both sides agree
+                schema: var("FROST_SCHEMA"),
+                table: var("FROST_TABLE"),
+                volume: var("FROST_VOLUME"),
+                gap_filled_table: var("FROST_GAP_FILLED_TABLE"),
+                access_token: var("DATABRICKS_ACCESS_TOKEN").map(Secret),
+                client_id: var("DATABRICKS_CLIENT_ID"),
+                client_secret: var("DATABRICKS_CLIENT_SECRET").map(Secret),
+                max_statement_bytes: parse_var("FROST_MAX_STATEMENT_BYTES", var)?,
+                warehouse_timeout_secs: parse_var("FROST_WAREHOUSE_TIMEOUT", var)?,
+                statement_timeout_secs: parse_var("FROST_STATEMENT_TIMEOUT", var)?,
+            },
+            validation: ValidationLayer::default(),
+            outliers: OutliersLayer::default(),
+            gap_fill: GapFillLayer::default(),
+        })
+    }
+
+    /// Fields set in `other` win.
+    fn merge(self, other: ConfigLayer) -> Self {
+        ConfigLayer {
+            frost: FrostLayer {
+                client_id: other.frost.client_id.or(self.frost.client_id),
+            },
+            databricks: DatabricksLayer {
+                hostname: other.databricks.hostname.or(self.databricks.hostname),
+                http_path: other.databricks.http_path.or(self.databricks.http_path),
+                catalog: other.databricks.catalog.or(self.databricks.catalog),
+                schema: other.databricks.schema.or(self.databricks.schema),
+                table: other.databricks.table.or(self.databricks.table),
+                volume: other.databricks.volume.or(self.databricks.volume),
+                gap_filled_table: other
+                    .databricks
+                    .gap_filled_table
+                    .or(self.databricks.gap_filled_table),
+                access_token: other
+                    .databricks
+                    .access_token
+                    .or(self.databricks.access_token),
+                client_id: other.databricks.client_id.or(self.databricks.client_id),
+                client_secret: other
+                    .databricks
+                    .client_secret
+                    .or(self.databricks.client_secret),
+                max_statement_bytes: other
+                    .databricks
+                    .max_statement_bytes
+                    .or(self.databricks.max_statement_bytes),
+                warehouse_timeout_secs: other
+                    .databricks
+                    .warehouse_timeout_secs
+                    .or(self.databricks.warehouse_timeout_secs),
+                statement_timeout_secs: other
+                    .databricks
+                    .statement_timeout_secs
+                    .or(self.databricks.statement_timeout_secs),
+            },
+            validation: ValidationLayer {
+                negative: other.validation.negative.or(self.validation.negative),
+                implausible_total: other
+                    .validation
+                    .implausible_total
+                    .or(self.validation.implausible_total),
+                max_daily_mm: other.validation.max_daily_mm.or(self.validation.max_daily_mm),
+                duplicate: other.validation.duplicate.or(self.validation.duplicate),
+                out_of_range: other.validation.out_of_range.or(self.validation.out_of_range),
+            },
+            outliers: OutliersLayer {
+                threshold: other.outliers.threshold.or(self.outliers.threshold),
+                min_neighbours: other
+                    .outliers
+                    .min_neighbours
+                    .or(self.outliers.min_neighbours),
+                min_mad_mm: other.outliers.min_mad_mm.or(self.outliers.min_mad_mm),
+            },
+            gap_fill: GapFillLayer {
+                min_donors: other.gap_fill.min_donors.or(self.gap_fill.min_donors),
+                min_overlap_days: other
+                    .gap_fill
+                    .min_overlap_days
+                    .or(self.gap_fill.min_overlap_days),
+            },
+        }
+    }
+}
+
+/// Parse a numeric environment variable, if set.
+fn parse_var<T: std::str::FromStr>(
+    key: &str,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<Option<T>> {
+    var(key)
+        .map(|v| {
+            v.parse().map_err(|_| {
+                FrostCliError::config(format!("Invalid {key} '{v}': expected a number"))
+            })
+        })
+        .transpose()
+}
+
+/// `frost.toml`: base settings plus named profiles layered on top with `--profile`.
+///
+/// ```toml
+/// [databricks]
+/// hostname = "my-instance.cloud.databricks.com"
+/// http_path = "/sql/1.0/warehouses/abc123"
+///
+/// [profiles.dev.databricks]
+/// schema = "dev_raw_frost"
+///
+/// [profiles.prod.databricks]
+/// catalog = "workspace"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    frost: FrostLayer,
+    databricks: DatabricksLayer,
+    validation: ValidationLayer,
+    outliers: OutliersLayer,
+    gap_fill: GapFillLayer,
+    profiles: BTreeMap<String, ConfigLayer>,
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            FrostCliError::config(format!(
+                "Could not read config file {}: {e}",
+                path.display()
+            ))
+        })?;
+        toml::from_str(&text).map_err(|e| {
+            FrostCliError::config(format!("Invalid config file {}: {e}", path.display()))
+        })
+    }
+}
+
+/// Where to load configuration from, and the CLI-flag layer to put on top.
+#[derive(Debug, Default)]
+pub struct ConfigOptions {
+    /// Config file; defaults to `frost.toml` in the working directory, if present.
+    pub file: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub overrides: ConfigLayer,
+}
+
+/// Merged settings from all layers. Sections are only checked for completeness when a
+/// subcommand asks for them, so e.g. `stations` works without Databricks settings.
 #[derive(Debug)]
 pub struct Config {
-    pub frost_client_id: String,
-    pub databricks_hostname: String,
-    pub databricks_http_path: String,
-    pub databricks_catalog: String,
-    pub databricks_access_token: String,
+    settings: ConfigLayer,
 }
 
 impl Config {
-    pub fn load() -> Result<Self> {
-        dotenvy::from_filename("../.env").or_else(|_| dotenvy::dotenv()).ok();
+    pub fn load(options: &ConfigOptions) -> Result<Self> {
+        // Searches the working directory and its parents, so running from frost/
+        // still picks up the project root .env
+        dotenvy::dotenv().ok();
 
-        let get = |key: &str| -> Result<String> {
-            std::env::var(key).map_err(|_| FrostCliError::EnvVar(key.to_string()))
+        let file = match &options.file {
+            Some(path) => Some(ConfigFile::read(path)?),
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(ConfigFile::read(Path::new(DEFAULT_CONFIG_FILE))?)
+            }
+            None => None,
         };
 
-        Ok(Config {
-            frost_client_id: get("FROST_CLIENT_ID")?,
-            databricks_hostname: get("DATABRICKS_HOSTNAME")?,
-            databricks_http_path: get("DATABRICKS_HTTP_PATH")?,
-            databricks_catalog: get("DATABRICKS_CATALOG")?,
-            databricks_access_token: get("DATABRICKS_ACCESS_TOKEN")?,
-        })
+        let mut settings = ConfigLayer::defaults();
+        match (file, &options.profile) {
+            (Some(mut file), profile) => {
+                settings = settings.merge(ConfigLayer {
+                    frost: file.frost,
+                    databricks: file.databricks,
+                    validation: file.validation,
+                    outliers: file.outliers,
+                    gap_fill: file.gap_fill,
+                });
+                if let Some(name) = profile {
+                    let layer = file.profiles.remove(name).ok_or_else(|| {
+                        let known: Vec<&str> = file.profiles.keys().map(String::as_str).collect();
+                        FrostCliError::config(format!(
+                            "Unknown profile '{name}' (config file has: {})",
+                            if known.is_empty() {
+                                "none".to_string()
+                            } else {
+                                known.join(", ")
+                            }
+                        ))
+                    })?;
+                    settings = settings.merge(layer);
+                }
+            }
+            (None, Some(name)) => {
+                return Err(FrostCliError::config(format!(
+                    "Profile '{name}' requested, but no config file was found ({DEFAULT_CONFIG_FILE} or --config)"
+                )));
+            }
+            (None, None) => {}
+        }
+        let settings = settings
+            .merge(ConfigLayer::from_env()?)
+            .merge(options.overrides.clone());
+
+        // Secrets print as *** here
+        debug!(profile = ?options.profile, ?settings, "Resolved configuration");
+        Ok(Config { settings })
     }
 
-    /// Load only the Frost client ID (for station listing without Databricks).
-    pub fn load_frost_only() -> Result<Self> {
-        dotenvy::from_filename("../.env").or_else(|_| dotenvy::dotenv()).ok();
+    /// Settings for the Frost API; errors if the client ID is missing.
+    pub fn frost(&self) -> Result<FrostConfig> {
+        let frost = &self.settings.frost;
+        match &frost.client_id {
+            Some(client_id) => Ok(FrostConfig {
+                client_id: client_id.clone(),
+            }),
+            None => Err(missing("frost", &["client_id (FROST_CLIENT_ID)"])),
+        }
+    }
 
-        let frost_client_id = std::env::var("FROST_CLIENT_ID")
-            .map_err(|_| FrostCliError::EnvVar("FROST_CLIENT_ID".to_string()))?;
+    /// Settings for the Databricks SQL warehouse; errors listing every missing field.
+    pub fn databricks(&self) -> Result<DatabricksConfig> {
+        let db = &self.settings.databricks;
+        // OAuth M2M when configured, else the personal access token
+        let auth = match (&db.client_id, &db.client_secret, &db.access_token) {
+            (Some(client_id), Some(client_secret), _) => Some(DatabricksAuth::OAuth {
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+            }),
+            (Some(_), None, _) | (None, Some(_), _) => {
+                return Err(FrostCliError::config(
+                    "databricks.client_id and databricks.client_secret must be set together \
+                     (DATABRICKS_CLIENT_ID, DATABRICKS_CLIENT_SECRET)",
+                ));
+            }
+            (None, None, token) => token.clone().map(DatabricksAuth::Token),
+        };
+        match (
+            &db.hostname,
+            &db.http_path,
+            &db.catalog,
+            &db.schema,
+            &db.table,
+            &db.volume,
+            auth,
+        ) {
+            (
+                Some(hostname),
+                Some(http_path),
+                Some(catalog),
+                Some(schema),
+                Some(table),
+                Some(volume),
+                Some(auth),
+            ) => {
+                // One per target table, so dev and prod tables don't share it
+                let gap_filled_table = db
+                    .gap_filled_table
+                    .clone()
+                    .unwrap_or_else(|| format!("{table}_gap_filled"));
+                // Interpolated into SQL, so only plain identifiers
+                for (field, name) in [
+                    ("catalog", catalog),
+                    ("schema", schema),
+                    ("table", table),
+                    ("volume", volume),
+                    ("gap_filled_table", &gap_filled_table),
+                ] {
+                    validate_identifier(field, name)?;
+                }
+                let max_statement_bytes = db
+                    .max_statement_bytes
+                    .unwrap_or(DEFAULT_MAX_STATEMENT_BYTES);
+                if max_statement_bytes == 0 || max_statement_bytes > STATEMENT_API_LIMIT_BYTES {
+                    return Err(FrostCliError::config(format!(
+                        "Invalid databricks.max_statement_bytes {max_statement_bytes}: \
+                         must be between 1 and {STATEMENT_API_LIMIT_BYTES}"
+                    )));
+                }
+                Ok(DatabricksConfig {
+                    hostname: hostname.clone(),
+                    http_path: http_path.clone(),
+                    catalog: catalog.clone(),
+                    schema: schema.clone(),
+                    table: table.clone(),
+                    volume: volume.clone(),
+                    gap_filled_table,
+                    auth,
+                    max_statement_bytes,
+                    warehouse_timeout_secs: db
+                        .warehouse_timeout_secs
+                        .unwrap_or(DEFAULT_WAREHOUSE_TIMEOUT_SECS),
+                    statement_timeout_secs: db
+                        .statement_timeout_secs
+                        .unwrap_or(DEFAULT_STATEMENT_TIMEOUT_SECS),
+                })
+            }
+            _ => {
+                let fields = [
+                    (db.hostname.is_none(), "hostname (DATABRICKS_HOSTNAME)"),
+                    (db.http_path.is_none(), "http_path (DATABRICKS_HTTP_PATH)"),
+                    (db.catalog.is_none(), "catalog (DATABRICKS_CATALOG)"),
+                    (db.schema.is_none(), "schema (FROST_SCHEMA)"),
+                    (db.table.is_none(), "table (FROST_TABLE)"),
+                    (db.volume.is_none(), "volume (FROST_VOLUME)"),
+                    (
+                        db.access_token.is_none() && db.client_id.is_none(),
+                        "access_token (DATABRICKS_ACCESS_TOKEN) or client_id + client_secret \
+                         (DATABRICKS_CLIENT_ID, DATABRICKS_CLIENT_SECRET)",
+                    ),
+                ];
+                let unset: Vec<&str> = fields.iter().filter(|f| f.0).map(|f| f.1).collect();
+                Err(missing("databricks", &unset))
+            }
+        }
+    }
+
+    /// Validation rule actions; every rule has a default, so this only checks values.
+    pub fn validation(&self) -> Result<ValidationRules> {
+        let v = &self.settings.validation;
+        let max_daily_mm = v.max_daily_mm.unwrap_or(DEFAULT_MAX_DAILY_MM);
+        if max_daily_mm.is_nan() || max_daily_mm <= 0.0 {
+            return Err(FrostCliError::config(format!(
+                "Invalid validation.max_daily_mm {max_daily_mm}: must be positive"
+            )));
+        }
+        let out_of_range = v.out_of_range.unwrap_or(RuleAction::Reject);
+        if out_of_range == RuleAction::Flag {
+            return Err(FrostCliError::config(
+                "Invalid validation.out_of_range \"flag\": use \"reject\" or \"fail\" \
+                 (flagged rows would land outside the range a re-run replaces)",
+            ));
+        }
+        Ok(ValidationRules {
+            negative: v.negative.unwrap_or(RuleAction::Reject),
+            implausible_total: v.implausible_total.unwrap_or(RuleAction::Flag),
+            max_daily_mm,
+            duplicate: v.duplicate.unwrap_or(RuleAction::Reject),
+            out_of_range,
+        })
+    }
+
+    /// Spatial outlier thresholds; all have defaults, so this only checks values.
+    pub fn outliers(&self) -> Result<OutlierSettings> {
+        let o = &self.settings.outliers;
+        let threshold = o.threshold.unwrap_or(DEFAULT_OUTLIER_THRESHOLD);
+        let min_mad_mm = o.min_mad_mm.unwrap_or(DEFAULT_OUTLIER_MIN_MAD_MM);
+        let min_neighbours = o.min_neighbours.unwrap_or(DEFAULT_OUTLIER_MIN_NEIGHBOURS);
+        if threshold.is_nan() || threshold <= 0.0 {
+            return Err(FrostCliError::config(format!(
+                "Invalid outliers.threshold {threshold}: must be positive"
+            )));
+        }
+        if min_mad_mm.is_nan() || min_mad_mm <= 0.0 {
+            return Err(FrostCliError::config(format!(
+                "Invalid outliers.min_mad_mm {min_mad_mm}: must be positive"
+            )));
+        }
+        if min_neighbours < 2 {
+            return Err(FrostCliError::config(format!(
+                "Invalid outliers.min_neighbours {min_neighbours}: must be at least 2"
+            )));
+        }
+        Ok(OutlierSettings {
+            threshold,
+            min_neighbours,
+            min_mad_mm,
+        })
+    }
 
-        Ok(Config {
-            frost_client_id,
-            databricks_hostname: String::new(),
-            databricks_http_path: String::new(),
-            databricks_catalog: String::new(),
-            databricks_access_token: String::new(),
+    /// Gap filling thresholds; all have defaults, so this only checks values.
+    pub fn gap_fill(&self) -> Result<GapFillSettings> {
+        let g = &self.settings.gap_fill;
+        let min_donors = g.min_donors.unwrap_or(DEFAULT_GAP_FILL_MIN_DONORS);
+        let min_overlap_days = g
+            .min_overlap_days
+            .unwrap_or(DEFAULT_GAP_FILL_MIN_OVERLAP_DAYS);
+        if min_donors == 0 {
+            return Err(FrostCliError::config(
+                "Invalid gap_fill.min_donors 0: must be at least 1",
+            ));
+        }
+        if min_overlap_days == 0 {
+            return Err(FrostCliError::config(
+                "Invalid gap_fill.min_overlap_days 0: must be at least 1",
+            ));
+        }
+        Ok(GapFillSettings {
+            min_donors,
+            min_overlap_days,
         })
     }
+}
+
+fn validate_identifier(field: &str, name: &str) -> Result<()> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(FrostCliError::config(format!(
+            "Invalid databricks.{field} '{name}': use letters, digits and underscores only"
+        )))
+    }
+}
+
+fn missing(section: &str, fields: &[&str]) -> FrostCliError {
+    FrostCliError::config(format!(
+        "Missing [{section}] settings: {} — set them in {DEFAULT_CONFIG_FILE}, the environment or .env",
+        fields.join(", ")
+    ))
+}
+
+#[derive(Debug, Clone)]
+pub struct FrostConfig {
+    pub client_id: Secret,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabricksConfig {
+    pub hostname: String,
+    pub http_path: String,
+    pub catalog: String,
+    pub schema: String,
+    pub table: String,
+    pub volume: String,
+    pub gap_filled_table: String,
+    pub auth: DatabricksAuth,
+    pub max_statement_bytes: usize,
+    pub warehouse_timeout_secs: u64,
+    pub statement_timeout_secs: u64,
+}
+
+/// How to authenticate against the Databricks workspace.
+#[derive(Debug, Clone)]
+pub enum DatabricksAuth {
+    /// Personal access token, sent as is.
+    Token(Secret),
+    /// Service principal; tokens come from the workspace `/oidc/v1/token` endpoint.
+    OAuth {
+        client_id: String,
+        client_secret: Secret,
+    },
+}
+
+impl DatabricksConfig {
+    /// Workspace URL; the Statement Execution API's chunk links are relative to it.
+    pub fn base_url(&self) -> String {
+        format!("https://{}", self.hostname)
+    }
+
+    pub fn sql_url(&self) -> String {
+        format!("https://{}/api/2.0/sql/statements", self.hostname)
+    }
+
+    /// OAuth token endpoint for machine-to-machine (client credentials) auth.
+    pub fn token_url(&self) -> String {
+        format!("https://{}/oidc/v1/token", self.hostname)
+    }
+
+    /// Files API base; append a `/Volumes/...` path.
+    pub fn files_url(&self) -> String {
+        format!("https://{}/api/2.0/fs/files", self.hostname)
+    }
 
-    pub fn databricks_sql_url(&self) -> String {
+    /// SQL Warehouses API endpoint for the warehouse in `http_path`.
+    pub fn warehouse_url(&self) -> String {
         format!(
-            "https://{}/api/2.0/sql/statements",
-            self.databricks_hostname
+            "https://{}/api/2.0/sql/warehouses/{}",
+            self.hostname,
+            self.warehouse_id()
         )
     }
 
     pub fn warehouse_id(&self) -> &str {
-        self.databricks_http_path
-            .rsplit('/')
-            .next()
-            .unwrap_or(&self.databricks_http_path)
+        self.http_path.rsplit('/').next().unwrap_or(&self.http_path)
     }
 }
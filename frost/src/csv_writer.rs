@@ -1,5 +1,7 @@
-use crate::error::Result;
+use crate::error::{FrostCliError, Result};
 use crate::frost_client::PrecipitationRow;
+use crate::gap_fill::{FilledRow, IMPUTATION_METHOD};
+use std::io::Write;
 use std::path::Path;
 use tracing::info;
 
@@ -7,7 +9,58 @@ pub fn write_csv(rows: &[PrecipitationRow], path: &Path) -> Result<usize> {
     info!(path = %path.display(), rows = rows.len(), "Writing CSV");
 
     let mut wtr = csv::Writer::from_path(path)?;
+    write_rows(&mut wtr, rows)?;
 
+    info!(rows = rows.len(), "CSV written successfully");
+    Ok(rows.len())
+}
+
+/// Gap-filled series from `ingest --fill-gaps`, with the columns of the gap-filled table.
+pub fn write_gap_filled_csv(rows: &[FilledRow], path: &Path) -> Result<usize> {
+    info!(path = %path.display(), rows = rows.len(), "Writing gap-filled CSV");
+
+    let mut wtr = csv::Writer::from_path(path)?;
+    wtr.write_record([
+        "station_id",
+        "station_name",
+        "el_area",
+        "reference_time",
+        "precipitation_mm",
+        "imputed",
+        "imputation_method",
+        "donor_stations",
+    ])?;
+
+    for row in rows {
+        let (method, donors) = match row.donor_stations {
+            Some(n) => (IMPUTATION_METHOD, n.to_string()),
+            None => ("", String::new()),
+        };
+        wtr.write_record([
+            &row.station_id,
+            &row.station_name,
+            &row.el_area,
+            &row.reference_time,
+            &row.precipitation_mm.to_string(),
+            &row.imputed().to_string(),
+            method,
+            &donors,
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(rows.len())
+}
+
+/// The same CSV as [`write_csv`], in memory (for uploading to a Databricks volume).
+pub fn to_csv_bytes(rows: &[PrecipitationRow]) -> Result<Vec<u8>> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    write_rows(&mut wtr, rows)?;
+    wtr.into_inner()
+        .map_err(|e| FrostCliError::Io(e.into_error()))
+}
+
+fn write_rows<W: Write>(wtr: &mut csv::Writer<W>, rows: &[PrecipitationRow]) -> Result<()> {
     wtr.write_record([
         "station_id",
         "station_name",
@@ -15,6 +68,9 @@ pub fn write_csv(rows: &[PrecipitationRow], path: &Path) -> Result<usize> {
         "reference_time",
         "precipitation_mm",
         "quality_code",
+        "validation_flags",
+        "spatial_z",
+        "spatial_outlier",
     ])?;
 
     for row in rows {
@@ -22,6 +78,9 @@ pub fn write_csv(rows: &[PrecipitationRow], path: &Path) -> Result<usize> {
             .precipitation_mm
             .map_or(String::new(), |v| v.to_string());
         let quality = row.quality_code.map_or(String::new(), |v| v.to_string());
+        let flags = row.validation_flags().unwrap_or_default();
+        let z = row.spatial_z.map_or(String::new(), |v| v.to_string());
+        let outlier = row.spatial_outlier.map_or(String::new(), |v| v.to_string());
 
         wtr.write_record([
             &row.station_id,
@@ -30,10 +89,12 @@ pub fn write_csv(rows: &[PrecipitationRow], path: &Path) -> Result<usize> {
             &row.reference_time,
             &precip,
             &quality,
+            &flags,
+            &z,
+            &outlier,
         ])?;
     }
 
     wtr.flush()?;
-    info!(rows = rows.len(), "CSV written successfully");
-    Ok(rows.len())
+    Ok(())
 }
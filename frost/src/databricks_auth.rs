@@ -0,0 +1,106 @@
+use crate::config::{DatabricksAuth, DatabricksConfig, Secret};
+use crate::error::{FrostCliError, Result};
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Refresh OAuth tokens this long before they expire, so no request goes out with
+/// a token that lapses while a statement is still running.
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// Bearer tokens for Databricks requests: either a fixed personal access token, or
+/// OAuth M2M tokens from the client credentials grant, cached until shortly before
+/// they expire.
+pub struct TokenProvider {
+    source: Source,
+}
+
+enum Source {
+    Static(String),
+    OAuth {
+        token_url: String,
+        client_id: String,
+        client_secret: Secret,
+        cached: Mutex<Option<CachedToken>>,
+    },
+}
+
+struct CachedToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// Lifetime in seconds (one hour for Databricks).
+    expires_in: u64,
+}
+
+impl TokenProvider {
+    pub fn new(config: &DatabricksConfig) -> Self {
+        let source = match &config.auth {
+            DatabricksAuth::Token(token) => Source::Static(token.expose().to_string()),
+            DatabricksAuth::OAuth {
+                client_id,
+                client_secret,
+            } => Source::OAuth {
+                token_url: config.token_url(),
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+                cached: Mutex::new(None),
+            },
+        };
+        Self { source }
+    }
+
+    /// A token valid for at least [`REFRESH_MARGIN`], fetching a new one if needed.
+    pub fn token(&self, client: &reqwest::blocking::Client) -> Result<String> {
+        let (token_url, client_id, client_secret, cached) = match &self.source {
+            Source::Static(token) => return Ok(token.clone()),
+            Source::OAuth {
+                token_url,
+                client_id,
+                client_secret,
+                cached,
+            } => (token_url, client_id, client_secret, cached),
+        };
+
+        // Held across the request so concurrent batches wait for one refresh
+        let mut cached = cached.lock().unwrap();
+        if let Some(token) = cached.as_ref()
+            && Instant::now() < token.refresh_at
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let resp = client
+            .post(token_url)
+            .basic_auth(client_id, Some(client_secret.expose()))
+            .form(&[("grant_type", "client_credentials"), ("scope", "all-apis")])
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!(
+                "OAuth token request for client {client_id} failed: HTTP {status}: {text}"
+            )));
+        }
+
+        let token: TokenResponse = resp.json()?;
+        let lifetime = Duration::from_secs(token.expires_in);
+        info!(
+            client_id = %client_id,
+            expires_in_secs = token.expires_in,
+            "Fetched Databricks OAuth token"
+        );
+        let access_token = token.access_token;
+        *cached = Some(CachedToken {
+            access_token: access_token.clone(),
+            refresh_at: Instant::now() + lifetime.saturating_sub(REFRESH_MARGIN),
+        });
+        Ok(access_token)
+    }
+}
@@ -1,26 +1,89 @@
-use crate::config::Config;
+use crate::config::DatabricksConfig;
+use crate::csv_writer;
+use crate::databricks_auth::TokenProvider;
 use crate::error::{FrostCliError, Result};
 use crate::frost_client::PrecipitationRow;
+use crate::gap_fill::{FilledRow, IMPUTATION_METHOD};
+use crate::runs::CLI_VERSION;
+use crate::telemetry;
 use serde::Deserialize;
-use tracing::{debug, info};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, info_span, warn};
 
-const BATCH_SIZE: usize = 5_000;
+/// Upper bound on rows per INSERT statement; batches are usually cut earlier by
+/// `max_statement_bytes`.
+pub const BATCH_SIZE: usize = 5_000;
+
+/// How often to check the warehouse state while waiting for it to start.
+const WAREHOUSE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often to check on a statement still running after the initial wait.
+const STATEMENT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 pub struct DatabricksClient {
     client: reqwest::blocking::Client,
+    base_url: String,
     sql_url: String,
+    files_url: String,
+    warehouse_url: String,
     warehouse_id: String,
-    access_token: String,
+    warehouse_timeout: Duration,
+    statement_timeout: Duration,
+    auth: TokenProvider,
     catalog: String,
+    schema: String,
+    table: String,
+    volume: String,
+    gap_filled_table: String,
+    max_statement_bytes: usize,
+    statements: AtomicUsize,
 }
 
 #[derive(Debug, Deserialize)]
 struct SqlResponse {
+    statement_id: Option<String>,
     status: Option<SqlStatus>,
-    #[allow(dead_code)]
-    manifest: Option<serde_json::Value>,
-    #[allow(dead_code)]
-    result: Option<serde_json::Value>,
+    manifest: Option<ResultManifest>,
+    result: Option<ResultChunk>,
+}
+
+/// Result metadata; only the column schema is used.
+#[derive(Debug, Deserialize)]
+struct ResultManifest {
+    schema: Option<ResultSchema>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultSchema {
+    #[serde(default)]
+    columns: Vec<ResultColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultColumn {
+    name: String,
+    /// BOOLEAN, INT, LONG, DOUBLE, DECIMAL, STRING, DATE, TIMESTAMP, ...
+    type_name: Option<String>,
+}
+
+/// One chunk of an inline result in `JSON_ARRAY` format: every value is a string
+/// or null. Further chunks are fetched from `next_chunk_internal_link`.
+#[derive(Debug, Deserialize)]
+struct ResultChunk {
+    data_array: Option<Vec<Vec<Option<String>>>>,
+    next_chunk_internal_link: Option<String>,
+}
+
+/// The part of the SQL Warehouses API response we use.
+#[derive(Debug, Deserialize)]
+struct WarehouseInfo {
+    /// STARTING, RUNNING, STOPPING, STOPPED, DELETING or DELETED
+    state: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,139 +98,769 @@ struct SqlError {
 }
 
 impl DatabricksClient {
-    pub fn new(config: &Config) -> Self {
+    pub fn new(config: &DatabricksConfig) -> Self {
         Self {
             client: reqwest::blocking::Client::new(),
-            sql_url: config.databricks_sql_url(),
+            base_url: config.base_url(),
+            sql_url: config.sql_url(),
+            files_url: config.files_url(),
+            warehouse_url: config.warehouse_url(),
             warehouse_id: config.warehouse_id().to_string(),
-            access_token: config.databricks_access_token.clone(),
-            catalog: config.databricks_catalog.clone(),
+            warehouse_timeout: Duration::from_secs(config.warehouse_timeout_secs),
+            statement_timeout: Duration::from_secs(config.statement_timeout_secs),
+            auth: TokenProvider::new(config),
+            catalog: config.catalog.clone(),
+            schema: config.schema.clone(),
+            table: config.table.clone(),
+            volume: config.volume.clone(),
+            gap_filled_table: config.gap_filled_table.clone(),
+            max_statement_bytes: config.max_statement_bytes,
+            statements: AtomicUsize::new(0),
         }
     }
 
+    pub fn warehouse_id(&self) -> &str {
+        &self.warehouse_id
+    }
+
+    /// How long [`Self::wait_for_warehouse`] waits before giving up.
+    pub fn warehouse_timeout(&self) -> Duration {
+        self.warehouse_timeout
+    }
+
+    /// How long one statement may run before it is cancelled.
+    pub fn statement_timeout(&self) -> Duration {
+        self.statement_timeout
+    }
+
+    /// Current state of the SQL warehouse, e.g. `RUNNING` or `STOPPED`.
+    pub fn warehouse_state(&self) -> Result<String> {
+        let resp = self
+            .client
+            .get(&self.warehouse_url)
+            .bearer_auth(self.auth.token(&self.client)?)
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!(
+                "Warehouse {}: HTTP {status}: {text}",
+                self.warehouse_id
+            )));
+        }
+        Ok(resp.json::<WarehouseInfo>()?.state)
+    }
+
+    /// Ask a stopped warehouse to start, without waiting for it. Returns the state
+    /// it was in, so callers can tell whether it was already running.
+    pub fn start_warehouse(&self) -> Result<String> {
+        let state = self.warehouse_state()?;
+        match state.as_str() {
+            "STOPPED" => {
+                info!(warehouse = %self.warehouse_id, "Starting SQL warehouse");
+                self.warehouse_action("start")?;
+            }
+            "DELETING" | "DELETED" => {
+                return Err(FrostCliError::databricks(format!(
+                    "Warehouse {} is {state}",
+                    self.warehouse_id
+                )));
+            }
+            _ => {}
+        }
+        Ok(state)
+    }
+
+    /// Start the warehouse if needed and wait until it is RUNNING, for at most the
+    /// configured `warehouse_timeout_secs`. A warehouse that is still stopping is
+    /// started again once it has stopped.
+    pub fn wait_for_warehouse(&self) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            let state = self.start_warehouse()?;
+            if state == "RUNNING" {
+                info!(
+                    warehouse = %self.warehouse_id,
+                    waited_secs = started.elapsed().as_secs(),
+                    "SQL warehouse is running"
+                );
+                return Ok(());
+            }
+            if started.elapsed() >= self.warehouse_timeout {
+                return Err(FrostCliError::databricks(format!(
+                    "Warehouse {} not RUNNING after {}s (state: {state})",
+                    self.warehouse_id,
+                    self.warehouse_timeout.as_secs()
+                )));
+            }
+            debug!(warehouse = %self.warehouse_id, state, "Waiting for SQL warehouse");
+            thread::sleep(WAREHOUSE_POLL_INTERVAL);
+        }
+    }
+
+    /// Ask the warehouse to stop. Statements still running on it are cancelled.
+    pub fn stop_warehouse(&self) -> Result<()> {
+        info!(warehouse = %self.warehouse_id, "Stopping SQL warehouse");
+        self.warehouse_action("stop")
+    }
+
+    fn warehouse_action(&self, action: &str) -> Result<()> {
+        let resp = self
+            .client
+            .post(format!("{}/{action}", self.warehouse_url))
+            .bearer_auth(self.auth.token(&self.client)?)
+            .json(&serde_json::json!({}))
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!(
+                "Could not {action} warehouse {}: HTTP {status}: {text}",
+                self.warehouse_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Configured cap on the SQL text of one INSERT statement.
+    pub fn max_statement_bytes(&self) -> usize {
+        self.max_statement_bytes
+    }
+
+    /// Number of SQL statements submitted so far.
+    pub fn statement_count(&self) -> usize {
+        self.statements.load(Ordering::Relaxed)
+    }
+
     fn execute_sql(&self, sql: &str) -> Result<SqlResponse> {
+        let kind = statement_kind(sql);
+        let span = info_span!("databricks.statement", kind, sql_len = sql.len());
+        let _enter = span.enter();
+
         debug!(sql_len = sql.len(), "Executing SQL statement");
+        self.statements.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let result = self
+            .send_statement(sql)
+            .and_then(|resp| self.wait_for_statement(resp, started));
+        telemetry::record_databricks_statement(kind, result.is_ok(), started.elapsed());
+        result
+    }
+
+    /// Ask Databricks to cancel a running statement.
+    fn cancel_statement(&self, id: &str) -> Result<()> {
+        let resp = self
+            .client
+            .post(format!("{}/{id}/cancel", self.sql_url))
+            .bearer_auth(self.auth.token(&self.client)?)
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!(
+                "Could not cancel statement {id}: HTTP {status}: {text}"
+            )));
+        }
+        Ok(())
+    }
 
+    fn send_statement(&self, sql: &str) -> Result<SqlResponse> {
         let body = serde_json::json!({
             "warehouse_id": self.warehouse_id,
             "catalog": self.catalog,
-            "schema": "raw_frost",
+            "schema": self.schema,
             "statement": sql,
             "wait_timeout": "30s",
             "disposition": "INLINE",
+            "format": "JSON_ARRAY",
         });
 
         let resp = self
             .client
             .post(&self.sql_url)
-            .bearer_auth(&self.access_token)
+            .bearer_auth(self.auth.token(&self.client)?)
             .json(&body)
             .send()?;
 
         let status = resp.status();
         if !status.is_success() {
             let text = resp.text().unwrap_or_default();
+            // Only the request itself is checked for size; a statement that was accepted
+            // and then FAILED is an ordinary SQL error, whatever its message says
+            if status == reqwest::StatusCode::PAYLOAD_TOO_LARGE
+                || (status.is_client_error() && is_size_rejection(&text))
+            {
+                return Err(FrostCliError::StatementTooLarge {
+                    bytes: sql.len(),
+                    message: format!("HTTP {status}: {text}"),
+                });
+            }
             return Err(FrostCliError::databricks(format!(
                 "HTTP {status}: {text}"
             )));
         }
 
         let sql_resp: SqlResponse = resp.json()?;
+        check_failed(sql_resp)
+    }
+
+    /// Poll a statement that is still PENDING or RUNNING after the initial wait
+    /// until it finishes. Errors if it failed, was cancelled or closed, or is still
+    /// running `statement_timeout_secs` after `started`, in which case it is cancelled.
+    fn wait_for_statement(&self, mut resp: SqlResponse, started: Instant) -> Result<SqlResponse> {
+        loop {
+            let state = resp.status.as_ref().map(|s| s.state.as_str());
+            match state {
+                Some("SUCCEEDED") | None => return Ok(resp),
+                Some("PENDING" | "RUNNING") => {}
+                Some(state) => {
+                    return Err(FrostCliError::databricks(format!(
+                        "Statement ended in state {state}"
+                    )));
+                }
+            }
+            let Some(id) = resp.statement_id.as_deref() else {
+                return Err(FrostCliError::databricks(
+                    "Statement still running, but the response has no statement_id",
+                ));
+            };
+            if started.elapsed() >= self.statement_timeout {
+                // Best effort; the timeout is the error worth reporting
+                if let Err(e) = self.cancel_statement(id) {
+                    warn!(statement_id = id, error = %e, "Could not cancel statement");
+                }
+                return Err(FrostCliError::databricks(format!(
+                    "Statement {id} still {} after {}s; cancelled",
+                    state.unwrap_or_default(),
+                    self.statement_timeout.as_secs()
+                )));
+            }
+            debug!(statement_id = id, state, "Waiting for statement");
+            thread::sleep(STATEMENT_POLL_INTERVAL);
 
-        if let Some(ref st) = sql_resp.status
-            && st.state == "FAILED"
-        {
-            let msg = st
-                .error
-                .as_ref()
-                .and_then(|e| e.message.as_deref())
-                .unwrap_or("Unknown SQL error");
-            return Err(FrostCliError::databricks(msg));
+            let url = format!("{}/{id}", self.sql_url);
+            resp = check_failed(self.get_json(&url)?)?;
         }
+    }
+
+    /// GET a Statement Execution API resource (statement status, result chunk).
+    fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.auth.token(&self.client)?)
+            .send()?;
 
-        Ok(sql_resp)
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!("HTTP {status}: {text}")));
+        }
+        Ok(resp.json()?)
     }
 
-    pub fn create_schema(&self) -> Result<()> {
-        info!(catalog = %self.catalog, "Creating schema if not exists");
-        self.execute_sql(&format!(
-            "CREATE SCHEMA IF NOT EXISTS {}.raw_frost",
-            self.catalog
-        ))?;
+    /// Run a statement that returns no rows (DDL, DELETE, INSERT).
+    pub fn execute(&self, sql: &str) -> Result<()> {
+        self.execute_sql(sql)?;
         Ok(())
     }
 
-    pub fn create_table(&self) -> Result<()> {
-        info!("Creating table if not exists");
-        let sql = format!(
-            r#"CREATE TABLE IF NOT EXISTS {catalog}.raw_frost.precipitation (
-    station_id       STRING  NOT NULL,
-    station_name     STRING  NOT NULL,
-    el_area          STRING  NOT NULL,
-    reference_time   DATE    NOT NULL,
-    precipitation_mm DOUBLE,
-    quality_code     INT,
-    ingested_at      TIMESTAMP
-)"#,
-            catalog = self.catalog
-        );
-        self.execute_sql(&sql)?;
+    /// Run a query and decode each row into `T`, matching columns to fields by name.
+    ///
+    /// `JSON_ARRAY` results carry every value as a string; values are converted to
+    /// JSON numbers and booleans according to the column types in the manifest
+    /// first, so `T` can use `i64`, `f64`, `bool`, `String` and `Option`s of them.
+    /// Results split into several chunks are followed to the last one.
+    pub fn query<T: DeserializeOwned>(&self, sql: &str) -> Result<Vec<T>> {
+        let resp = self.execute_sql(sql)?;
+        let columns = resp
+            .manifest
+            .and_then(|m| m.schema)
+            .map(|s| s.columns)
+            .unwrap_or_default();
+
+        let mut rows = Vec::new();
+        let mut chunk = resp.result;
+        while let Some(current) = chunk {
+            for row in current.data_array.unwrap_or_default() {
+                rows.push(decode_row(&columns, row)?);
+            }
+            chunk = match current.next_chunk_internal_link {
+                Some(link) => Some(self.get_json(&format!("{}{link}", self.base_url))?),
+                None => None,
+            };
+        }
+        Ok(rows)
+    }
+
+    pub fn create_schema(&self) -> Result<()> {
+        info!(catalog = %self.catalog, schema = %self.schema, "Creating schema if not exists");
+        self.execute_sql(&self.create_schema_sql())?;
         Ok(())
     }
 
+    /// Current Delta version of the precipitation table.
+    pub fn table_version(&self) -> Result<i64> {
+        self.version_of(&self.table_name())
+    }
+
+    /// Current Delta version of the gap-filled table.
+    pub fn gap_filled_table_version(&self) -> Result<i64> {
+        self.version_of(&self.gap_filled_table_name())
+    }
+
+    fn version_of(&self, table: &str) -> Result<i64> {
+        #[derive(Deserialize)]
+        struct History {
+            version: i64,
+        }
+
+        let history: Vec<History> = self.query(&format!("DESCRIBE HISTORY {table} LIMIT 1"))?;
+        history
+            .first()
+            .map(|h| h.version)
+            .ok_or_else(|| FrostCliError::databricks(format!("No history for {table}")))
+    }
+
     pub fn delete_existing(&self, from: &str, to: &str) -> Result<()> {
         info!(from, to, "Deleting existing rows for date range");
-        let sql = format!(
-            "DELETE FROM {catalog}.raw_frost.precipitation \
+        self.execute_sql(&self.delete_existing_sql(from, to))?;
+        Ok(())
+    }
+
+    /// Fully qualified name of the raw schema.
+    pub fn schema_name(&self) -> String {
+        format!("{}.{}", self.catalog, self.schema)
+    }
+
+    /// Fully qualified name of the staging volume.
+    pub fn volume_name(&self) -> String {
+        format!("{}.{}", self.schema_name(), self.volume)
+    }
+
+    /// Path of a file in the staging volume, as used by the Files API and `COPY INTO`.
+    pub fn volume_path(&self, file_name: &str) -> String {
+        format!(
+            "/Volumes/{}/{}/{}/{file_name}",
+            self.catalog, self.schema, self.volume
+        )
+    }
+
+    /// Fully qualified name of the precipitation table.
+    pub fn table_name(&self) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, self.table)
+    }
+
+    /// Fully qualified name of the table `ingest --fill-gaps` writes to.
+    pub fn gap_filled_table_name(&self) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, self.gap_filled_table)
+    }
+
+    pub fn create_schema_sql(&self) -> String {
+        format!("CREATE SCHEMA IF NOT EXISTS {}", self.schema_name())
+    }
+
+    pub fn delete_existing_sql(&self, from: &str, to: &str) -> String {
+        format!(
+            "DELETE FROM {table} \
+             WHERE reference_time >= '{from}' AND reference_time < '{to}'",
+            table = self.table_name(),
+        )
+    }
+
+    pub fn delete_gap_filled_sql(&self, from: &str, to: &str) -> String {
+        format!(
+            "DELETE FROM {table} \
              WHERE reference_time >= '{from}' AND reference_time < '{to}'",
-            catalog = self.catalog,
+            table = self.gap_filled_table_name(),
+        )
+    }
+
+    /// INSERT statement for one batch; `values` is the comma-separated tuple list.
+    pub fn insert_sql(&self, values: &str) -> String {
+        format!(
+            "INSERT INTO {table} \
+             (station_id, station_name, el_area, reference_time, precipitation_mm, quality_code, \
+              ingested_at, ingest_run_id, cli_version, validation_flags, spatial_z, spatial_outlier) \
+             VALUES {values}",
+            table = self.table_name(),
+        )
+    }
+
+    /// INSERT statement for one batch of gap-filled rows.
+    pub fn insert_gap_filled_sql(&self, values: &str) -> String {
+        format!(
+            "INSERT INTO {table} \
+             (station_id, station_name, el_area, reference_time, precipitation_mm, imputed, \
+              imputation_method, donor_stations, ingested_at, ingest_run_id, cli_version) \
+             VALUES {values}",
+            table = self.gap_filled_table_name(),
+        )
+    }
+
+    /// Insert rows in batches, stamping each with `run_id` and the CLI version.
+    pub fn insert_precipitation(
+        &self,
+        rows: &[PrecipitationRow],
+        run_id: &str,
+        concurrency: usize,
+    ) -> Result<usize> {
+        let values: Vec<String> = rows.iter().map(|r| value_tuple(r, run_id)).collect();
+        self.insert_values(&self.insert_sql(""), &values, concurrency)
+    }
+
+    /// Replace the gap-filled rows for `from..to` with `rows`.
+    pub fn write_gap_filled(
+        &self,
+        rows: &[FilledRow],
+        from: &str,
+        to: &str,
+        run_id: &str,
+        concurrency: usize,
+    ) -> Result<usize> {
+        info!(table = %self.gap_filled_table_name(), from, to, "Replacing gap-filled rows");
+        self.execute_sql(&self.delete_gap_filled_sql(from, to))?;
+        let values: Vec<String> = rows.iter().map(|r| gap_filled_tuple(r, run_id)).collect();
+        self.insert_values(&self.insert_gap_filled_sql(""), &values, concurrency)
+    }
+
+    /// Run `head` (an INSERT up to and including `VALUES `) with `values` in batches.
+    ///
+    /// Batches are cut by the byte length of the generated statement (at most
+    /// `max_statement_bytes`) as well as by [`BATCH_SIZE`] rows. A batch that
+    /// Databricks still rejects as too large is split in half and retried.
+    ///
+    /// Up to `concurrency` batches are in flight at once. After a failure no new
+    /// batches are started; the failures are returned in batch order.
+    fn insert_values(&self, head: &str, values: &[String], concurrency: usize) -> Result<usize> {
+        if values.is_empty() {
+            return Ok(0);
+        }
+
+        let batches = split_batches(values, head.len(), self.max_statement_bytes);
+        let workers = concurrency.clamp(1, batches.len());
+        info!(
+            batches = batches.len(),
+            concurrency = workers,
+            "Inserting rows"
         );
-        self.execute_sql(&sql)?;
-        Ok(())
+
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let inserted = AtomicUsize::new(0);
+        let errors: Mutex<Vec<(usize, FrostCliError)>> = Mutex::new(Vec::new());
+        let parent = tracing::Span::current();
+
+        thread::scope(|s| {
+            for _ in 0..workers {
+                s.spawn(|| {
+                    // Spans are per-thread; keep batches under the caller's span
+                    let _enter = parent.enter();
+                    while !stop.load(Ordering::Relaxed) {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(batch) = batches.get(idx) else {
+                            break;
+                        };
+                        match self.insert_batch(head, idx + 1, batch) {
+                            Ok(n) => {
+                                inserted.fetch_add(n, Ordering::Relaxed);
+                            }
+                            Err(e) => {
+                                error!(batch = idx + 1, error = %e, "Insert batch failed");
+                                stop.store(true, Ordering::Relaxed);
+                                errors.lock().unwrap().push((idx + 1, e));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut errors = errors.into_inner().unwrap();
+        if !errors.is_empty() {
+            errors.sort_by_key(|(batch, _)| *batch);
+            return Err(FrostCliError::InsertFailed(errors));
+        }
+        Ok(inserted.into_inner())
     }
 
-    pub fn insert_precipitation(&self, rows: &[PrecipitationRow]) -> Result<usize> {
+    fn insert_batch(&self, head: &str, batch: usize, values: &[String]) -> Result<usize> {
+        let sql = format!("{head}{}", values.join(", "));
+
+        let span = info_span!(
+            "databricks.insert_batch",
+            batch,
+            rows = values.len(),
+            bytes = sql.len()
+        );
+        let _enter = span.enter();
+
+        info!(
+            batch,
+            rows = values.len(),
+            bytes = sql.len(),
+            "Inserting batch"
+        );
+        match self.execute_sql(&sql) {
+            Ok(_) => {
+                telemetry::record_databricks_rows_inserted(values.len());
+                Ok(values.len())
+            }
+            Err(FrostCliError::StatementTooLarge { bytes, .. }) if values.len() > 1 => {
+                warn!(
+                    batch,
+                    rows = values.len(),
+                    bytes,
+                    "Batch rejected as too large, splitting in half"
+                );
+                let (first, second) = values.split_at(values.len() / 2);
+                Ok(self.insert_batch(head, batch, first)?
+                    + self.insert_batch(head, batch, second)?)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `COPY INTO` for a CSV written by [`csv_writer::to_csv_bytes`] at `path` in the volume.
+    pub fn copy_into_sql(&self, path: &str, run_id: &str) -> String {
+        format!(
+            "COPY INTO {table} \
+             FROM (SELECT station_id, station_name, el_area, \
+                   CAST(reference_time AS DATE) AS reference_time, \
+                   CAST(precipitation_mm AS DOUBLE) AS precipitation_mm, \
+                   CAST(quality_code AS INT) AS quality_code, \
+                   CURRENT_TIMESTAMP() AS ingested_at, \
+                   {run_id} AS ingest_run_id, {version} AS cli_version, validation_flags, \
+                   CAST(spatial_z AS DOUBLE) AS spatial_z, \
+                   CAST(spatial_outlier AS BOOLEAN) AS spatial_outlier \
+                   FROM {path}) \
+             FILEFORMAT = CSV \
+             FORMAT_OPTIONS ('header' = 'true') \
+             COPY_OPTIONS ('force' = 'true')",
+            table = self.table_name(),
+            run_id = sql_literal(run_id),
+            version = sql_literal(CLI_VERSION),
+            path = sql_literal(path),
+        )
+    }
+
+    /// Load rows by uploading them as one CSV to the staging volume and running
+    /// `COPY INTO`, instead of many INSERT statements. The file is removed afterwards.
+    pub fn bulk_load_precipitation(
+        &self,
+        rows: &[PrecipitationRow],
+        run_id: &str,
+    ) -> Result<usize> {
         if rows.is_empty() {
             return Ok(0);
         }
 
-        let mut total_inserted = 0;
-
-        for (batch_idx, chunk) in rows.chunks(BATCH_SIZE).enumerate() {
-            let values: Vec<String> = chunk
-                .iter()
-                .map(|r| {
-                    let precip = match r.precipitation_mm {
-                        Some(v) => v.to_string(),
-                        None => "NULL".to_string(),
-                    };
-                    let quality = match r.quality_code {
-                        Some(v) => v.to_string(),
-                        None => "NULL".to_string(),
-                    };
-                    format!(
-                        "('{station_id}', '{station_name}', '{el_area}', '{ref_time}', {precip}, {quality}, CURRENT_TIMESTAMP())",
-                        station_id = r.station_id,
-                        station_name = r.station_name.replace('\'', "''"),
-                        el_area = r.el_area,
-                        ref_time = r.reference_time,
-                    )
-                })
-                .collect();
-
-            let sql = format!(
-                "INSERT INTO {catalog}.raw_frost.precipitation \
-                 (station_id, station_name, el_area, reference_time, precipitation_mm, quality_code, ingested_at) \
-                 VALUES {values}",
-                catalog = self.catalog,
-                values = values.join(", "),
-            );
-
-            info!(batch = batch_idx + 1, rows = chunk.len(), "Inserting batch");
-            self.execute_sql(&sql)?;
-            total_inserted += chunk.len();
-        }
-
-        Ok(total_inserted)
+        let span = info_span!("databricks.bulk_load", rows = rows.len());
+        let _enter = span.enter();
+
+        let path = self.volume_path(&format!("{run_id}.csv"));
+        let csv = csv_writer::to_csv_bytes(rows)?;
+        info!(path = %path, bytes = csv.len(), rows = rows.len(), "Uploading CSV to volume");
+        self.upload_file(&path, csv)?;
+
+        #[derive(Deserialize)]
+        struct CopyResult {
+            num_inserted_rows: usize,
+        }
+
+        info!(table = %self.table_name(), "Loading with COPY INTO");
+        let loaded: Result<Vec<CopyResult>> = self.query(&self.copy_into_sql(&path, run_id));
+
+        // Best effort: a leftover staging file is harmless and named after the run
+        if let Err(e) = self.delete_file(&path) {
+            warn!(path = %path, error = %e, "Could not remove staging file");
+        }
+
+        let inserted = loaded?.first().map_or(rows.len(), |r| r.num_inserted_rows);
+        telemetry::record_databricks_rows_inserted(inserted);
+        Ok(inserted)
+    }
+
+    fn upload_file(&self, path: &str, contents: Vec<u8>) -> Result<()> {
+        let resp = self
+            .client
+            .put(format!("{}{path}", self.files_url))
+            .query(&[("overwrite", "true")])
+            .bearer_auth(self.auth.token(&self.client)?)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(contents)
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(FrostCliError::databricks(format!(
+                "Upload to {path} failed: HTTP {status}: {text}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn delete_file(&self, path: &str) -> Result<()> {
+        let resp = self
+            .client
+            .delete(format!("{}{path}", self.files_url))
+            .bearer_auth(self.auth.token(&self.client)?)
+            .send()?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(FrostCliError::databricks(format!(
+                "Delete of {path} failed: HTTP {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Turn a `FAILED` statement response into an error.
+fn check_failed(resp: SqlResponse) -> Result<SqlResponse> {
+    if let Some(ref st) = resp.status
+        && st.state == "FAILED"
+    {
+        let msg = st
+            .error
+            .as_ref()
+            .and_then(|e| e.message.as_deref())
+            .unwrap_or("Unknown SQL error");
+        return Err(FrostCliError::databricks(msg));
     }
+    Ok(resp)
+}
+
+/// Decode one `JSON_ARRAY` row into `T` via a JSON object keyed by column name.
+fn decode_row<T: DeserializeOwned>(
+    columns: &[ResultColumn],
+    row: Vec<Option<String>>,
+) -> Result<T> {
+    let object = columns
+        .iter()
+        .zip(row)
+        .map(|(column, value)| {
+            (
+                column.name.clone(),
+                typed_value(column.type_name.as_deref(), value),
+            )
+        })
+        .collect();
+    serde_json::from_value(Value::Object(object))
+        .map_err(|e| FrostCliError::databricks(format!("Could not decode result row: {e}")))
+}
+
+/// JSON value for a `JSON_ARRAY` cell of the given column type. Types without a
+/// JSON counterpart (dates, timestamps, ...) and unparsable values stay strings.
+fn typed_value(type_name: Option<&str>, value: Option<String>) -> Value {
+    let Some(value) = value else {
+        return Value::Null;
+    };
+    let typed = match type_name {
+        Some("BYTE" | "SHORT" | "INT" | "LONG") => value.parse::<i64>().ok().map(Value::from),
+        Some("FLOAT" | "DOUBLE" | "DECIMAL") => value.parse::<f64>().ok().map(Value::from),
+        Some("BOOLEAN") => value.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    typed.unwrap_or(Value::String(value))
+}
+
+/// One `VALUES` tuple for a gap-filled INSERT.
+fn gap_filled_tuple(r: &FilledRow, run_id: &str) -> String {
+    let (method, donors) = match r.donor_stations {
+        Some(n) => (sql_literal(IMPUTATION_METHOD), n.to_string()),
+        None => ("NULL".to_string(), "NULL".to_string()),
+    };
+    format!(
+        "('{station_id}', '{station_name}', '{el_area}', '{ref_time}', {precip}, {imputed}, \
+         {method}, {donors}, CURRENT_TIMESTAMP(), '{run_id}', '{CLI_VERSION}')",
+        station_id = r.station_id,
+        station_name = r.station_name.replace('\'', "''"),
+        el_area = r.el_area,
+        ref_time = r.reference_time,
+        precip = r.precipitation_mm,
+        imputed = r.imputed(),
+    )
+}
+
+/// One `VALUES` tuple for an INSERT.
+fn value_tuple(r: &PrecipitationRow, run_id: &str) -> String {
+    let precip = match r.precipitation_mm {
+        Some(v) => v.to_string(),
+        None => "NULL".to_string(),
+    };
+    let quality = match r.quality_code {
+        Some(v) => v.to_string(),
+        None => "NULL".to_string(),
+    };
+    let flags = r
+        .validation_flags()
+        .map_or("NULL".to_string(), |f| sql_literal(&f));
+    let z = r.spatial_z.map_or("NULL".to_string(), |v| v.to_string());
+    let outlier = r
+        .spatial_outlier
+        .map_or("NULL".to_string(), |v| v.to_string());
+    format!(
+        "('{station_id}', '{station_name}', '{el_area}', '{ref_time}', {precip}, {quality}, \
+         CURRENT_TIMESTAMP(), '{run_id}', '{CLI_VERSION}', {flags}, {z}, {outlier})",
+        station_id = r.station_id,
+        station_name = r.station_name.replace('\'', "''"),
+        el_area = r.el_area,
+        ref_time = r.reference_time,
+    )
+}
+
+/// Group `values` into consecutive batches whose INSERT (a `prefix_len`-byte statement
+/// head plus the `", "`-joined tuples) stays within `max_bytes` and [`BATCH_SIZE`] rows.
+/// A single tuple that alone exceeds `max_bytes` still gets its own batch.
+fn split_batches(values: &[String], prefix_len: usize, max_bytes: usize) -> Vec<&[String]> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut len = prefix_len;
+    for (i, value) in values.iter().enumerate() {
+        let added = if i == start {
+            value.len()
+        } else {
+            value.len() + 2
+        };
+        if i > start && (len + added > max_bytes || i - start == BATCH_SIZE) {
+            batches.push(&values[start..i]);
+            start = i;
+            len = prefix_len + value.len();
+        } else {
+            len += added;
+        }
+    }
+    batches.push(&values[start..]);
+    batches
+}
+
+/// Whether the body of a rejected request says the request was too big.
+fn is_size_rejection(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    [
+        "too large",
+        "too big",
+        "exceeds the maximum",
+        "size limit",
+        "size exceeded",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+/// Quote `s` as a SQL string literal. Databricks treats backslash as an escape
+/// character inside literals, so it's doubled along with single quotes.
+pub fn sql_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+/// Leading SQL keyword, used as a low-cardinality label ("CREATE", "INSERT", ...).
+fn statement_kind(sql: &str) -> &str {
+    sql.split_whitespace().next().unwrap_or("")
 }
@@ -0,0 +1,55 @@
+use crate::frost_client::PrecipitationRow;
+use std::cmp::Ordering;
+use tracing::{info, warn};
+
+/// Remove rows that repeat another row's station, date, element and time offset.
+///
+/// Touching yearly chunks can return the same observation twice. Of each group the
+/// best row is kept: lowest quality code first (0 is verified, a missing code ranks
+/// last), then a row with a value over one without, then the lower value, so the
+/// choice doesn't depend on which request returned first. The result is sorted by
+/// station and date. Returns the kept rows and how many were removed.
+pub fn dedup(mut rows: Vec<PrecipitationRow>) -> (Vec<PrecipitationRow>, usize) {
+    let before = rows.len();
+    rows.sort_by(|a, b| {
+        a.station_id
+            .cmp(&b.station_id)
+            .then_with(|| a.reference_time.cmp(&b.reference_time))
+            .then_with(|| a.element_id.cmp(&b.element_id))
+            .then_with(|| a.time_offset.cmp(&b.time_offset))
+            .then_with(|| rank(a, b))
+    });
+    // Keeps the first of each run of equal keys, which is the best-ranked row
+    rows.dedup_by(|later, kept| same_key(later, kept));
+
+    let removed = before - rows.len();
+    if removed > 0 {
+        warn!(removed, rows = rows.len(), "Removed duplicate observations");
+    } else {
+        info!(rows = rows.len(), "No duplicate observations");
+    }
+    (rows, removed)
+}
+
+fn same_key(a: &PrecipitationRow, b: &PrecipitationRow) -> bool {
+    a.station_id == b.station_id
+        && a.reference_time == b.reference_time
+        && a.element_id == b.element_id
+        && a.time_offset == b.time_offset
+}
+
+/// Order of preference between two rows with the same key; `Less` means `a` wins.
+fn rank(a: &PrecipitationRow, b: &PrecipitationRow) -> Ordering {
+    let quality = |r: &PrecipitationRow| (r.quality_code.is_none(), r.quality_code);
+    quality(a)
+        .cmp(&quality(b))
+        .then_with(|| {
+            a.precipitation_mm
+                .is_none()
+                .cmp(&b.precipitation_mm.is_none())
+        })
+        .then_with(|| match (a.precipitation_mm, b.precipitation_mm) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            _ => Ordering::Equal,
+        })
+}
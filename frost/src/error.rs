@@ -1,16 +1,102 @@
 use std::fmt;
 
+/// Errors from the frost CLI.
+///
+/// Each variant maps to a process exit code (see [`FrostCliError::exit_code`]) so
+/// schedulers can tell "retry later" apart from "fix the config":
+///
+/// | Code | Variant(s)                               | Retry?                  |
+/// |------|------------------------------------------|-------------------------|
+/// | 0    | (success)                                |                         |
+/// | 1    | `Http`, `Io`, `Csv`, `FrostApi`          | maybe                   |
+/// | 2    | `Config`, `EnvVar` (also clap usage)     | no — fix invocation     |
+/// | 3    | `NoData`                                 | no — nothing to fetch   |
+/// | 4    | `Unauthorized`                           | no — fix credentials    |
+/// | 5    | `BadRequest`                             | no — fix parameters     |
+/// | 6    | `RateLimited`                            | yes, after backoff      |
+/// | 7    | `ServerError`                            | yes                     |
+/// | 8    | `Timeout`, `Connection`                  | yes                     |
+/// | 9    | `Deserialize`                            | no — API contract issue |
+/// | 10   | `Databricks`, `StatementTooLarge`        | maybe                   |
+/// |      | `InsertFailed`                           |                         |
+/// | 11   | `Verification`, `Validation`             | no — check the data     |
+///
+/// `FetchFailed` (several failed chunks of a parallel fetch) exits with the code of
+/// its first non-retryable failure, or of its first failure if all are retryable.
 #[derive(Debug, thiserror::Error)]
 pub enum FrostCliError {
     #[error("HTTP request failed: {0}")]
     Http(#[from] reqwest::Error),
 
-    #[error("Frost API error: {message} (reason: {reason})")]
-    FrostApi { reason: String, message: String },
+    #[error("Frost API error: {message} (reason: {reason}) [{context}]")]
+    FrostApi {
+        reason: String,
+        message: String,
+        context: RequestContext,
+    },
+
+    #[error("No data available [{context}]")]
+    NoData { context: RequestContext },
+
+    #[error("Frost API rate limit hit{} [{context}]", retry_after_suffix(*.retry_after_secs))]
+    RateLimited {
+        retry_after_secs: Option<u64>,
+        context: RequestContext,
+    },
+
+    #[error("Frost API rejected credentials: {message} [{context}]")]
+    Unauthorized {
+        message: String,
+        context: RequestContext,
+    },
+
+    #[error("Frost API bad request: {message} [{context}]")]
+    BadRequest {
+        message: String,
+        context: RequestContext,
+    },
+
+    #[error("Frost API server error (HTTP {status}): {message} [{context}]")]
+    ServerError {
+        status: u16,
+        message: String,
+        context: RequestContext,
+    },
+
+    #[error("Frost API request timed out [{context}]")]
+    Timeout { context: RequestContext },
+
+    #[error("Could not reach Frost API: {source} [{context}]")]
+    Connection {
+        source: reqwest::Error,
+        context: RequestContext,
+    },
+
+    #[error("Could not decode Frost API response: {message} [{context}]")]
+    Deserialize {
+        message: String,
+        context: RequestContext,
+    },
+
+    #[error("{} fetch(es) failed:\n  {}", .0.len(), join_errors(.0))]
+    FetchFailed(Vec<FrostCliError>),
 
     #[error("Databricks SQL error: {0}")]
     Databricks(String),
 
+    #[error("Databricks rejected a {bytes}-byte statement as too large: {message}")]
+    StatementTooLarge { bytes: usize, message: String },
+
+    /// Failed INSERT batches as (batch number, error), in batch order.
+    #[error("{} insert batch(es) failed:\n  {}", .0.len(), join_batch_errors(.0))]
+    InsertFailed(Vec<(usize, FrostCliError)>),
+
+    #[error("Post-load verification failed: {0}")]
+    Verification(String),
+
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -24,6 +110,53 @@ pub enum FrostCliError {
     Csv(#[from] csv::Error),
 }
 
+/// Which Frost request an error belongs to.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub stations: Option<String>,
+    pub period: Option<String>,
+}
+
+impl RequestContext {
+    pub fn new(stations: impl Into<String>, period: impl Into<String>) -> Self {
+        Self {
+            stations: Some(stations.into()),
+            period: Some(period.into()),
+        }
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.stations, &self.period) {
+            (Some(s), Some(p)) => write!(f, "stations {s}, period {p}"),
+            (Some(s), None) => write!(f, "stations {s}"),
+            (None, Some(p)) => write!(f, "period {p}"),
+            (None, None) => write!(f, "no station/period"),
+        }
+    }
+}
+
+fn retry_after_suffix(secs: Option<u64>) -> String {
+    secs.map_or(String::new(), |s| format!(" (retry after {s}s)"))
+}
+
+fn join_errors(errors: &[FrostCliError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n  ")
+}
+
+fn join_batch_errors(errors: &[(usize, FrostCliError)]) -> String {
+    errors
+        .iter()
+        .map(|(batch, e)| format!("batch {batch}: {e}"))
+        .collect::<Vec<_>>()
+        .join("\n  ")
+}
+
 impl From<dotenvy::Error> for FrostCliError {
     fn from(e: dotenvy::Error) -> Self {
         FrostCliError::EnvVar(e.to_string())
@@ -33,10 +166,15 @@ impl From<dotenvy::Error> for FrostCliError {
 pub type Result<T> = std::result::Result<T, FrostCliError>;
 
 impl FrostCliError {
-    pub fn frost_api(reason: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn frost_api(
+        reason: impl Into<String>,
+        message: impl Into<String>,
+        context: &RequestContext,
+    ) -> Self {
         FrostCliError::FrostApi {
             reason: reason.into(),
             message: message.into(),
+            context: context.clone(),
         }
     }
 
@@ -47,4 +185,82 @@ impl FrostCliError {
     pub fn config(message: impl fmt::Display) -> Self {
         FrostCliError::Config(message.to_string())
     }
+
+    /// Wrap a transport-level reqwest error from a Frost request, keeping timeouts distinct.
+    pub fn request(e: reqwest::Error, context: &RequestContext) -> Self {
+        if e.is_timeout() {
+            FrostCliError::Timeout {
+                context: context.clone(),
+            }
+        } else {
+            FrostCliError::Connection {
+                source: e,
+                context: context.clone(),
+            }
+        }
+    }
+
+    /// Short snake_case name of the variant, for metrics labels and summaries.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FrostCliError::Http(_) => "http",
+            FrostCliError::FrostApi { .. } => "frost_api",
+            FrostCliError::NoData { .. } => "no_data",
+            FrostCliError::RateLimited { .. } => "rate_limited",
+            FrostCliError::Unauthorized { .. } => "unauthorized",
+            FrostCliError::BadRequest { .. } => "bad_request",
+            FrostCliError::ServerError { .. } => "server_error",
+            FrostCliError::Timeout { .. } => "timeout",
+            FrostCliError::Connection { .. } => "connection",
+            FrostCliError::Deserialize { .. } => "deserialize",
+            FrostCliError::FetchFailed(_) => "fetch_failed",
+            FrostCliError::Databricks(_) => "databricks",
+            FrostCliError::StatementTooLarge { .. } => "statement_too_large",
+            FrostCliError::InsertFailed(_) => "insert_failed",
+            FrostCliError::Verification(_) => "verification",
+            FrostCliError::Validation(_) => "validation",
+            FrostCliError::Config(_) => "config",
+            FrostCliError::EnvVar(_) => "env_var",
+            FrostCliError::Io(_) => "io",
+            FrostCliError::Csv(_) => "csv",
+        }
+    }
+
+    /// Transient failures that are worth retrying unchanged.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            FrostCliError::RateLimited { .. }
+                | FrostCliError::ServerError { .. }
+                | FrostCliError::Timeout { .. }
+                | FrostCliError::Connection { .. }
+        )
+    }
+
+    /// Process exit code for this error. See the table on [`FrostCliError`].
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            FrostCliError::Http(_)
+            | FrostCliError::FrostApi { .. }
+            | FrostCliError::Io(_)
+            | FrostCliError::Csv(_) => 1,
+            FrostCliError::Config(_) | FrostCliError::EnvVar(_) => 2,
+            FrostCliError::NoData { .. } => 3,
+            FrostCliError::Unauthorized { .. } => 4,
+            FrostCliError::BadRequest { .. } => 5,
+            FrostCliError::RateLimited { .. } => 6,
+            FrostCliError::ServerError { .. } => 7,
+            FrostCliError::Timeout { .. } | FrostCliError::Connection { .. } => 8,
+            FrostCliError::Deserialize { .. } => 9,
+            FrostCliError::Databricks(_)
+            | FrostCliError::StatementTooLarge { .. }
+            | FrostCliError::InsertFailed(_) => 10,
+            FrostCliError::Verification(_) | FrostCliError::Validation(_) => 11,
+            FrostCliError::FetchFailed(errors) => errors
+                .iter()
+                .find(|e| !e.is_retryable())
+                .or(errors.first())
+                .map_or(1, FrostCliError::exit_code),
+        }
+    }
 }
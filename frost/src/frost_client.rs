@@ -1,24 +1,39 @@
-use crate::config::{county_to_el_area, station_by_id, ElArea, Station};
-use crate::error::{FrostCliError, Result};
+use crate::config::{county_to_el_area, ElArea, FrostConfig, Station};
+use crate::error::{FrostCliError, RequestContext, Result};
+use crate::geo::el_area_for_point;
+use crate::telemetry;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use tracing::{info, warn};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tracing::{info, info_span, warn};
 
 const FROST_BASE_URL: &str = "https://frost.met.no/observations/v0.jsonld";
 const FROST_SOURCES_URL: &str = "https://frost.met.no/sources/v0.jsonld";
+const FROST_AVAILABLE_URL: &str =
+    "https://frost.met.no/observations/availableTimeSeries/v0.jsonld";
+
+/// Frost element we ingest. All requests filter on this.
+pub const PRECIPITATION_ELEMENT: &str = "sum(precipitation_amount P1D)";
+
+/// Time offset of the daily precipitation series we ingest (06 UTC to 06 UTC).
+pub const PRECIPITATION_TIME_OFFSET: &str = "PT6H";
+
+/// Max station IDs per availableTimeSeries request, to keep the URL reasonably short.
+pub const AVAILABLE_SOURCES_PER_REQUEST: usize = 50;
 
 pub struct FrostClient {
     client: reqwest::blocking::Client,
     client_id: String,
+    requests: AtomicUsize,
 }
 
-// --- Frost API response types (observations) ---
+// --- Frost API response envelope (shared by all endpoints) ---
 
 #[derive(Debug, Deserialize)]
-pub struct FrostResponse {
-    #[serde(rename = "@type")]
-    #[allow(dead_code)]
-    pub response_type: Option<String>,
-    pub data: Option<Vec<FrostObservationData>>,
+pub struct FrostResponse<T> {
+    pub data: Option<Vec<T>>,
     pub error: Option<FrostErrorBody>,
 }
 
@@ -28,6 +43,8 @@ pub struct FrostErrorBody {
     pub message: Option<String>,
 }
 
+// --- Frost API response types (observations) ---
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FrostObservationData {
@@ -42,16 +59,11 @@ pub struct FrostObservation {
     pub element_id: String,
     pub value: Option<f64>,
     pub quality_code: Option<i32>,
+    pub time_offset: Option<String>,
 }
 
 // --- Frost API response types (sources/stations) ---
 
-#[derive(Debug, Deserialize)]
-pub struct FrostSourcesResponse {
-    pub data: Option<Vec<FrostSource>>,
-    pub error: Option<FrostErrorBody>,
-}
-
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FrostSource {
@@ -59,11 +71,47 @@ pub struct FrostSource {
     pub name: Option<String>,
     pub county: Option<String>,
     pub municipality: Option<String>,
+    pub geometry: Option<FrostGeometry>,
     #[allow(dead_code)]
     pub valid_from: Option<String>,
     pub valid_to: Option<String>,
 }
 
+/// GeoJSON-style point: `coordinates` is `[longitude, latitude]`.
+#[derive(Debug, Deserialize)]
+pub struct FrostGeometry {
+    pub coordinates: Option<Vec<f64>>,
+}
+
+impl FrostGeometry {
+    fn lon_lat(&self) -> Option<(f64, f64)> {
+        match self.coordinates.as_deref() {
+            Some([lon, lat, ..]) => Some((*lon, *lat)),
+            _ => None,
+        }
+    }
+}
+
+// --- Frost API response types (availableTimeSeries) ---
+
+/// One time series Frost holds for a station/element/offset.
+/// `valid_to` is `None` while the series is still being produced.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableTimeSeries {
+    pub source_id: String,
+    pub valid_from: String,
+    pub valid_to: Option<String>,
+    pub time_offset: Option<String>,
+}
+
+impl AvailableTimeSeries {
+    /// Station ID without the sensor suffix ("SN18700:0" → "SN18700").
+    pub fn station_id(&self) -> &str {
+        self.source_id.split(':').next().unwrap_or(&self.source_id)
+    }
+}
+
 // --- Flattened output row ---
 
 #[derive(Debug, Clone)]
@@ -74,6 +122,25 @@ pub struct PrecipitationRow {
     pub reference_time: String,
     pub precipitation_mm: Option<f64>,
     pub quality_code: Option<i32>,
+    /// Frost element and time offset the value belongs to. Part of the dedup key,
+    /// not written: every stored row is [`PRECIPITATION_ELEMENT`] at
+    /// [`PRECIPITATION_TIME_OFFSET`].
+    pub element_id: String,
+    pub time_offset: Option<String>,
+    /// Names of validation rules the row broke but was kept for (see `validation`).
+    pub flags: Vec<&'static str>,
+    /// Robust z-score against other stations in the area that day, and whether it
+    /// crossed the threshold (see `outliers`). `None` when there were too few to compare.
+    pub spatial_z: Option<f64>,
+    pub spatial_outlier: Option<bool>,
+}
+
+impl PrecipitationRow {
+    /// `flags` as stored in the `validation_flags` column: comma-separated, or
+    /// `None` for a clean row.
+    pub fn validation_flags(&self) -> Option<String> {
+        (!self.flags.is_empty()).then(|| self.flags.join(","))
+    }
 }
 
 /// A discovered station from the Frost API.
@@ -83,18 +150,103 @@ pub struct DiscoveredStation {
     pub name: String,
     pub county: String,
     pub municipality: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
     pub el_area: Option<ElArea>,
     pub is_active: bool,
 }
 
 impl FrostClient {
-    pub fn new(client_id: String) -> Self {
+    pub fn new(config: &FrostConfig) -> Self {
         Self {
             client: reqwest::blocking::Client::new(),
-            client_id,
+            client_id: config.client_id.expose().to_string(),
+            requests: AtomicUsize::new(0),
         }
     }
 
+    /// Number of HTTP requests sent so far (all endpoints).
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// GET a Frost endpoint and return its `data` array.
+    ///
+    /// Failures are classified into typed errors carrying `context`; 404 and 412
+    /// ("No time series found") become [`FrostCliError::NoData`].
+    fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        url: &str,
+        query: &[(&str, &str)],
+        context: &RequestContext,
+    ) -> Result<Vec<T>> {
+        let span = info_span!(
+            "frost.request",
+            endpoint,
+            stations = context.stations.as_deref(),
+            period = context.period.as_deref(),
+        );
+        let _enter = span.enter();
+
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let result = self.send_get(url, query, context);
+        let outcome = result.as_ref().map_or_else(FrostCliError::kind, |_| "ok");
+        telemetry::record_frost_request(endpoint, outcome, started.elapsed());
+        result
+    }
+
+    fn send_get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        context: &RequestContext,
+    ) -> Result<Vec<T>> {
+        let resp = self
+            .client
+            .get(url)
+            .basic_auth(&self.client_id, Some(""))
+            .query(query)
+            .send()
+            .map_err(|e| FrostCliError::request(e, context))?;
+
+        let status = resp.status();
+        let retry_after_secs = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        let text = resp.text().map_err(|e| FrostCliError::request(e, context))?;
+
+        let body: FrostResponse<T> = match serde_json::from_str(&text) {
+            Ok(body) => body,
+            Err(e) if status.is_success() => {
+                return Err(FrostCliError::Deserialize {
+                    message: e.to_string(),
+                    context: context.clone(),
+                });
+            }
+            // Error pages are not always JSON; classify by status alone
+            Err(_) => FrostResponse {
+                data: None,
+                error: None,
+            },
+        };
+
+        if status.is_success() && body.error.is_none() {
+            return Ok(body.data.unwrap_or_default());
+        }
+
+        let (reason, message) = body
+            .error
+            .map(|e| (e.reason.unwrap_or_default(), e.message.unwrap_or_default()))
+            .unwrap_or_default();
+        Err(classify_error(status, reason, message, retry_after_secs, context))
+    }
+
+    /// Fetch daily precipitation for the stations in `from..to`.
+    /// Returns [`FrostCliError::NoData`] when Frost has nothing for the request.
     pub fn fetch_precipitation(
         &self,
         stations: &[&Station],
@@ -111,43 +263,21 @@ impl FrostClient {
 
         info!(stations = %sources, period = %reference_time, "Fetching precipitation from Frost API");
 
-        let resp = self
-            .client
-            .get(FROST_BASE_URL)
-            .basic_auth(&self.client_id, Some(""))
-            .query(&[
+        let context = RequestContext::new(&sources, &reference_time);
+        let data: Vec<FrostObservationData> = self.get(
+            "observations",
+            FROST_BASE_URL,
+            &[
                 ("sources", sources.as_str()),
-                ("elements", "sum(precipitation_amount P1D)"),
+                ("elements", PRECIPITATION_ELEMENT),
                 ("referencetime", reference_time.as_str()),
-                ("timeoffsets", "PT6H"),
-            ])
-            .send()?;
-
-        let status = resp.status();
-        let body: FrostResponse = resp.json()?;
-
-        if let Some(err) = body.error {
-            let reason = err.reason.unwrap_or_default();
-            // 404 "Not found" and 412 "No time series found" mean no data — not real errors
-            if reason == "Not found"
-                || status.as_u16() == 404
-                || status.as_u16() == 412
-            {
-                warn!(stations = %sources, period = %reference_time, "No data available (skipping)");
-                return Ok(Vec::new());
-            }
-            return Err(FrostCliError::frost_api(reason, err.message.unwrap_or_default()));
-        }
+                ("timeoffsets", PRECIPITATION_TIME_OFFSET),
+            ],
+            &context,
+        )?;
 
-        if !status.is_success() {
-            return Err(FrostCliError::frost_api(
-                status.as_str(),
-                "Unexpected error from Frost API",
-            ));
-        }
-
-        let data = body.data.unwrap_or_default();
-        let rows = flatten_observations(&data);
+        let rows = flatten_observations(&data, stations);
+        telemetry::record_frost_rows(rows.len());
 
         info!(rows = rows.len(), "Received observation data");
         Ok(rows)
@@ -157,42 +287,28 @@ impl FrostClient {
     pub fn list_precipitation_stations(&self) -> Result<Vec<DiscoveredStation>> {
         info!("Fetching available precipitation stations from Frost API");
 
-        let resp = self
-            .client
-            .get(FROST_SOURCES_URL)
-            .basic_auth(&self.client_id, Some(""))
-            .query(&[
+        let sources: Vec<FrostSource> = self.get(
+            "sources",
+            FROST_SOURCES_URL,
+            &[
                 ("types", "SensorSystem"),
-                ("elements", "sum(precipitation_amount P1D)"),
+                ("elements", PRECIPITATION_ELEMENT),
                 ("country", "NO"),
-            ])
-            .send()?;
-
-        let status = resp.status();
-        let body: FrostSourcesResponse = resp.json()?;
-
-        if let Some(err) = body.error {
-            return Err(FrostCliError::frost_api(
-                err.reason.unwrap_or_default(),
-                err.message.unwrap_or_default(),
-            ));
-        }
-
-        if !status.is_success() {
-            return Err(FrostCliError::frost_api(
-                status.as_str(),
-                "Unexpected error from Frost sources API",
-            ));
-        }
-
-        let sources = body.data.unwrap_or_default();
+            ],
+            &RequestContext::default(),
+        )?;
         info!(total = sources.len(), "Received station list");
 
         let stations: Vec<DiscoveredStation> = sources
             .into_iter()
             .map(|s| {
                 let county = s.county.unwrap_or_default();
-                let el_area = county_to_el_area(&county);
+                let lon_lat = s.geometry.as_ref().and_then(FrostGeometry::lon_lat);
+                // Coordinates are authoritative; county names only when there is no geometry
+                let el_area = match lon_lat {
+                    Some((lon, lat)) => el_area_for_point(lon, lat),
+                    None => county_to_el_area(&county),
+                };
                 let is_active = s.valid_to.is_none()
                     || s.valid_to.as_deref().is_some_and(|v| v > "2024-01-01");
 
@@ -201,6 +317,8 @@ impl FrostClient {
                     name: s.name.unwrap_or_default(),
                     county,
                     municipality: s.municipality.unwrap_or_default(),
+                    latitude: lon_lat.map(|(_, lat)| lat),
+                    longitude: lon_lat.map(|(lon, _)| lon),
                     el_area,
                     is_active,
                 }
@@ -209,9 +327,86 @@ impl FrostClient {
 
         Ok(stations)
     }
+
+    /// List the daily precipitation time series Frost holds for the given stations
+    /// that overlap `from..to`. Stations without any series are simply absent.
+    pub fn available_time_series(
+        &self,
+        station_ids: &[&str],
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<AvailableTimeSeries>> {
+        let reference_time = format!("{from}/{to}");
+        let mut series = Vec::new();
+
+        for batch in station_ids.chunks(AVAILABLE_SOURCES_PER_REQUEST) {
+            let sources = batch.join(",");
+            info!(stations = batch.len(), period = %reference_time, "Fetching available time series from Frost API");
+
+            let context = RequestContext::new(&sources, &reference_time);
+            let result = self.get(
+                "available_time_series",
+                FROST_AVAILABLE_URL,
+                &[
+                    ("sources", sources.as_str()),
+                    ("elements", PRECIPITATION_ELEMENT),
+                    ("referencetime", reference_time.as_str()),
+                ],
+                &context,
+            );
+
+            match result {
+                Ok(batch_series) => series.extend(batch_series),
+                // None of the stations in this batch have a matching series
+                Err(FrostCliError::NoData { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        info!(series = series.len(), "Received available time series");
+        Ok(series)
+    }
+}
+
+/// Map a failed Frost response to a typed error.
+fn classify_error(
+    status: StatusCode,
+    reason: String,
+    message: String,
+    retry_after_secs: Option<u64>,
+    context: &RequestContext,
+) -> FrostCliError {
+    let context = context.clone();
+    let message = if message.is_empty() { reason.clone() } else { message };
+
+    match status {
+        StatusCode::NOT_FOUND | StatusCode::PRECONDITION_FAILED => FrostCliError::NoData { context },
+        _ if reason == "Not found" => FrostCliError::NoData { context },
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            FrostCliError::Unauthorized { message, context }
+        }
+        StatusCode::TOO_MANY_REQUESTS => FrostCliError::RateLimited {
+            retry_after_secs,
+            context,
+        },
+        StatusCode::BAD_REQUEST => FrostCliError::BadRequest { message, context },
+        s if s.is_server_error() => FrostCliError::ServerError {
+            status: s.as_u16(),
+            message,
+            context,
+        },
+        s => FrostCliError::frost_api(
+            if reason.is_empty() { s.as_str().to_string() } else { reason },
+            message,
+            &context,
+        ),
+    }
 }
 
-fn flatten_observations(data: &[FrostObservationData]) -> Vec<PrecipitationRow> {
+fn flatten_observations(
+    data: &[FrostObservationData],
+    stations: &[&Station],
+) -> Vec<PrecipitationRow> {
     let mut rows = Vec::new();
 
     for entry in data {
@@ -222,7 +417,7 @@ fn flatten_observations(data: &[FrostObservationData]) -> Vec<PrecipitationRow>
             .next()
             .unwrap_or(&entry.source_id);
 
-        let station = station_by_id(station_id);
+        let station = stations.iter().find(|s| s.id == station_id);
         let station_name = station.map_or("Unknown", |s| s.name);
         let el_area = station.map_or_else(
             || {
@@ -241,7 +436,7 @@ fn flatten_observations(data: &[FrostObservationData]) -> Vec<PrecipitationRow>
             .unwrap_or(&entry.reference_time);
 
         for obs in &entry.observations {
-            if obs.element_id == "sum(precipitation_amount P1D)" {
+            if obs.element_id == PRECIPITATION_ELEMENT {
                 rows.push(PrecipitationRow {
                     station_id: station_id.to_string(),
                     station_name: station_name.to_string(),
@@ -249,6 +444,11 @@ fn flatten_observations(data: &[FrostObservationData]) -> Vec<PrecipitationRow>
                     reference_time: date.to_string(),
                     precipitation_mm: obs.value,
                     quality_code: obs.quality_code,
+                    element_id: obs.element_id.clone(),
+                    time_offset: obs.time_offset.clone(),
+                    flags: Vec::new(),
+                    spatial_z: None,
+                    spatial_outlier: None,
                 });
             }
         }
@@ -0,0 +1,158 @@
+use crate::config::Station;
+use crate::frost_client::PrecipitationRow;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Stored in `imputation_method` for filled values.
+pub const IMPUTATION_METHOD: &str = "normal_ratio";
+
+/// Thresholds for filling a missing station-day.
+#[derive(Debug, Clone)]
+pub struct GapFillSettings {
+    /// Neighbour stations with a usable value that day needed to fill it.
+    pub min_donors: usize,
+    /// Days both stations reported, needed before a neighbour's ratio is used.
+    pub min_overlap_days: usize,
+}
+
+/// One row of the gap-filled table: an observed value, or an estimate for a
+/// station-day Frost had no value for.
+#[derive(Debug, Clone)]
+pub struct FilledRow {
+    pub station_id: String,
+    pub station_name: String,
+    pub el_area: String,
+    pub reference_time: String,
+    pub precipitation_mm: f64,
+    /// Neighbour stations the value was estimated from; `None` for observed values.
+    pub donor_stations: Option<usize>,
+}
+
+impl FilledRow {
+    pub fn imputed(&self) -> bool {
+        self.donor_stations.is_some()
+    }
+}
+
+/// Outcome of gap filling for one run, included in the run summary.
+#[derive(Debug, Default, Serialize)]
+pub struct GapFillReport {
+    /// Stations times days in the requested period.
+    pub station_days: usize,
+    pub observed: usize,
+    pub filled: usize,
+    /// Missing station-days without enough usable neighbours; left out of the table.
+    pub unfilled: usize,
+    /// Rows written to the gap-filled table or CSV.
+    pub rows_written: usize,
+}
+
+/// Build a complete daily series per station for `from..to` by filling missing days
+/// with the normal ratio method.
+///
+/// A missing value at station S is estimated from each neighbour N in the same
+/// electricity area that reported that day as `value_N * total_S / total_N`, where
+/// the totals are summed over the days in this run both stations reported. The
+/// estimate is the mean over those neighbours. Spatial outliers are not used as
+/// donors or in the totals. Observed values, outliers included, are passed through.
+pub fn fill_gaps(
+    rows: &[PrecipitationRow],
+    stations: &[&'static Station],
+    from: NaiveDate,
+    to: NaiveDate,
+    settings: &GapFillSettings,
+) -> (Vec<FilledRow>, GapFillReport) {
+    let dates: Vec<String> = from
+        .iter_days()
+        .take_while(|d| *d < to)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect();
+
+    let mut observed: HashMap<(&str, &str), f64> = HashMap::new();
+    let mut usable: HashMap<(&str, &str), f64> = HashMap::new();
+    for row in rows {
+        if let Some(value) = row.precipitation_mm {
+            let key = (row.station_id.as_str(), row.reference_time.as_str());
+            observed.insert(key, value);
+            if row.spatial_outlier != Some(true) {
+                usable.insert(key, value);
+            }
+        }
+    }
+
+    // total_S / total_N per ordered station pair, if they overlap enough
+    let mut ratios: HashMap<(&str, &str), Option<f64>> = HashMap::new();
+    let mut ratio = |s: &'static str, n: &'static str| {
+        *ratios.entry((s, n)).or_insert_with(|| {
+            let (mut days, mut total_s, mut total_n) = (0, 0.0, 0.0);
+            for date in &dates {
+                if let (Some(vs), Some(vn)) = (
+                    usable.get(&(s, date.as_str())),
+                    usable.get(&(n, date.as_str())),
+                ) {
+                    days += 1;
+                    total_s += vs;
+                    total_n += vn;
+                }
+            }
+            (days >= settings.min_overlap_days && total_n > 0.0).then(|| total_s / total_n)
+        })
+    };
+
+    let mut report = GapFillReport {
+        station_days: stations.len() * dates.len(),
+        ..GapFillReport::default()
+    };
+    let mut filled = Vec::with_capacity(report.station_days);
+    for station in stations {
+        let neighbours: Vec<&Station> = stations
+            .iter()
+            .filter(|n| n.el_area == station.el_area && n.id != station.id)
+            .copied()
+            .collect();
+        for date in &dates {
+            let (value, donor_stations) = match observed.get(&(station.id, date.as_str())) {
+                Some(&value) => {
+                    report.observed += 1;
+                    (value, None)
+                }
+                None => {
+                    let estimates: Vec<f64> = neighbours
+                        .iter()
+                        .filter_map(|n| {
+                            let value = usable.get(&(n.id, date.as_str()))?;
+                            Some(value * ratio(station.id, n.id)?)
+                        })
+                        .collect();
+                    if estimates.len() < settings.min_donors {
+                        report.unfilled += 1;
+                        continue;
+                    }
+                    report.filled += 1;
+                    let mean = estimates.iter().sum::<f64>() / estimates.len() as f64;
+                    // Frost reports to 0.1 mm
+                    ((mean * 10.0).round() / 10.0, Some(estimates.len()))
+                }
+            };
+            filled.push(FilledRow {
+                station_id: station.id.to_string(),
+                station_name: station.name.to_string(),
+                el_area: station.el_area.to_string(),
+                reference_time: date.clone(),
+                precipitation_mm: value,
+                donor_stations,
+            });
+        }
+    }
+
+    info!(
+        station_days = report.station_days,
+        observed = report.observed,
+        filled = report.filled,
+        unfilled = report.unfilled,
+        "Filled missing station-days"
+    );
+    (filled, report)
+}
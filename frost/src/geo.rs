@@ -0,0 +1,113 @@
+use crate::config::ElArea;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// Simplified electricity bidding-zone boundaries (NO1–NO5), embedded at build time.
+/// Polygons are coarse and extend out to sea / across the border — they are only
+/// meant to classify Norwegian station coordinates, not to be drawn on a map.
+const EL_AREAS_GEOJSON: &str = include_str!("../data/el_areas.geojson");
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// --- GeoJSON types (only the subset we ship) ---
+
+#[derive(Debug, Deserialize)]
+struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Feature {
+    properties: FeatureProperties,
+    geometry: Geometry,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeatureProperties {
+    el_area: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "coordinates")]
+enum Geometry {
+    Polygon(Vec<Vec<[f64; 2]>>),
+    MultiPolygon(Vec<Vec<Vec<[f64; 2]>>>),
+}
+
+/// A polygon as a list of rings: the first is the outer boundary, the rest are holes.
+/// Coordinates are `[longitude, latitude]` as in GeoJSON.
+struct AreaPolygon {
+    el_area: ElArea,
+    rings: Vec<Vec<[f64; 2]>>,
+}
+
+fn area_polygons() -> &'static [AreaPolygon] {
+    static POLYGONS: OnceLock<Vec<AreaPolygon>> = OnceLock::new();
+    POLYGONS.get_or_init(|| {
+        let collection: FeatureCollection = serde_json::from_str(EL_AREAS_GEOJSON)
+            .expect("embedded el_areas.geojson is valid GeoJSON");
+
+        collection
+            .features
+            .into_iter()
+            .flat_map(|f| {
+                let el_area: ElArea = f
+                    .properties
+                    .el_area
+                    .parse()
+                    .expect("embedded el_areas.geojson has valid el_area properties");
+                let polygons = match f.geometry {
+                    Geometry::Polygon(rings) => vec![rings],
+                    Geometry::MultiPolygon(polys) => polys,
+                };
+                polygons
+                    .into_iter()
+                    .map(move |rings| AreaPolygon { el_area, rings })
+            })
+            .collect()
+    })
+}
+
+/// Ray-casting point-in-ring test.
+fn ring_contains(ring: &[[f64; 2]], lon: f64, lat: f64) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let [xi, yi] = ring[i];
+        let [xj, yj] = ring[j];
+        if (yi > lat) != (yj > lat) && lon < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl AreaPolygon {
+    fn contains(&self, lon: f64, lat: f64) -> bool {
+        match self.rings.split_first() {
+            Some((outer, holes)) => {
+                ring_contains(outer, lon, lat) && !holes.iter().any(|h| ring_contains(h, lon, lat))
+            }
+            None => false,
+        }
+    }
+}
+
+/// Classify a coordinate into an electricity area using the embedded boundaries.
+/// Returns `None` for points outside all areas (e.g. Svalbard, Jan Mayen).
+pub fn el_area_for_point(lon: f64, lat: f64) -> Option<ElArea> {
+    area_polygons()
+        .iter()
+        .find(|p| p.contains(lon, lat))
+        .map(|p| p.el_area)
+}
+
+/// Great-circle (haversine) distance between two latitude/longitude points.
+pub fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
@@ -0,0 +1,61 @@
+use crate::error::Result;
+use crate::progress::StderrWriter;
+use clap::ValueEnum;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+
+/// How log lines are rendered.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable, one line per event with the span chain as a prefix
+    #[default]
+    Full,
+    /// Like `full`, but shorter: span fields are appended to the event
+    Compact,
+    /// Multi-line, for reading a single run in a terminal
+    Pretty,
+    /// One JSON object per event, for log shippers
+    Json,
+}
+
+/// Build the `fmt` layer for the chosen format.
+///
+/// Logs go to stderr (above the progress bar, if one is drawn), so stdout stays
+/// clean for `--summary-json -`, or are appended to `file` when given. In JSON the
+/// enclosing spans (`ingest`, `fetch_chunk`, `frost.request`, `databricks.insert_batch`,
+/// ...) are emitted as `span` and `spans`, so `station`, `chunk` and `batch` can be
+/// indexed without parsing the message.
+pub fn layer<S>(format: LogFormat, file: Option<&Path>) -> Result<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let (writer, ansi) = match file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            (BoxMakeWriter::new(Mutex::new(file)), false)
+        }
+        None => (BoxMakeWriter::new(|| StderrWriter), true),
+    };
+
+    let base = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_writer(writer);
+
+    let layer = match format {
+        LogFormat::Full => base.with_ansi(ansi).boxed(),
+        LogFormat::Compact => base.compact().with_ansi(ansi).boxed(),
+        LogFormat::Pretty => base.pretty().with_ansi(ansi).boxed(),
+        LogFormat::Json => base
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    };
+    Ok(layer)
+}
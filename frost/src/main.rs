@@ -1,65 +1,253 @@
+mod availability;
+mod catalogue;
 mod config;
 mod csv_writer;
+mod dedup;
+mod databricks_auth;
 mod databricks_client;
 mod error;
 mod frost_client;
+mod gap_fill;
+mod geo;
+mod logging;
+mod migrations;
+mod outliers;
+mod plan;
+mod progress;
+mod reconcile;
+mod runs;
+mod suggest;
+mod summary;
+mod telemetry;
+mod validation;
 
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::{Condvar, Mutex};
 use std::thread;
 
-use clap::{Parser, Subcommand, ValueEnum};
-use config::{yearly_chunks, stations_for_areas, ElArea, Station};
+use availability::Availability;
+use catalogue::StationCatalogue;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::{parse_date, yearly_chunks, stations_for_areas, ConfigLayer, ConfigOptions, ElArea, Station};
 use databricks_client::DatabricksClient;
-use error::Result;
+use error::{FrostCliError, Result};
 use frost_client::{FrostClient, PrecipitationRow};
-use tracing::{error, info};
+use logging::LogFormat;
+use progress::Progress;
+use summary::{ChunkCounts, RunParameters, RunSummary};
+use tracing::{error, info, info_span, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 #[derive(Parser)]
 #[command(name = "frost", about = "Fetch precipitation data from frost.met.no and load into Databricks")]
 struct Cli {
     #[command(subcommand)]
     command: Command,
+
+    /// Export OpenTelemetry traces and metrics to this OTLP/HTTP endpoint, e.g. http://localhost:4318
+    #[arg(long, global = true, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    otel_endpoint: Option<String>,
+
+    /// Write OpenTelemetry spans and metrics as JSON lines to this file
+    #[arg(long, global = true)]
+    otel_file: Option<PathBuf>,
+
+    /// Log line format
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Full, env = "FROST_LOG_FORMAT")]
+    log_format: LogFormat,
+
+    /// Append logs to this file instead of writing them to stderr
+    #[arg(long, global = true, env = "FROST_LOG_FILE")]
+    log_file: Option<PathBuf>,
+
+    /// Config file (default: frost.toml in the working directory, if present)
+    #[arg(long, global = true, env = "FROST_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Config file profile to layer on top of the base settings, e.g. dev or prod
+    #[arg(long, global = true, env = "FROST_PROFILE")]
+    profile: Option<String>,
+
+    /// Databricks workspace hostname (overrides config file and DATABRICKS_HOSTNAME)
+    #[arg(long, global = true)]
+    databricks_hostname: Option<String>,
+
+    /// SQL warehouse HTTP path (overrides config file and DATABRICKS_HTTP_PATH)
+    #[arg(long, global = true)]
+    databricks_http_path: Option<String>,
+
+    /// Unity Catalog catalog (overrides config file and DATABRICKS_CATALOG)
+    #[arg(long, global = true)]
+    catalog: Option<String>,
+
+    /// Schema to write raw tables to, e.g. dev_raw_frost (overrides config file and FROST_SCHEMA)
+    #[arg(long, global = true)]
+    schema: Option<String>,
+
+    /// Precipitation table name (overrides config file and FROST_TABLE)
+    #[arg(long, global = true)]
+    table: Option<String>,
+}
+
+impl Cli {
+    /// Config sources; CLI flags form the top layer.
+    fn config_options(&self) -> ConfigOptions {
+        let mut overrides = ConfigLayer::default();
+        overrides.databricks.hostname = self.databricks_hostname.clone();
+        overrides.databricks.http_path = self.databricks_http_path.clone();
+        overrides.databricks.catalog = self.catalog.clone();
+        overrides.databricks.schema = self.schema.clone();
+        overrides.databricks.table = self.table.clone();
+        ConfigOptions {
+            file: self.config.clone(),
+            profile: self.profile.clone(),
+            overrides,
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Command {
     /// Fetch precipitation data and load into Databricks or CSV
-    Ingest {
-        /// Start date (inclusive), e.g. 2024-01-01
-        #[arg(long)]
-        from: String,
+    Ingest(IngestArgs),
 
-        /// End date (exclusive), e.g. 2024-02-01
-        #[arg(long)]
-        to: String,
+    /// List available precipitation weather stations from the Frost API
+    #[command(args_conflicts_with_subcommands = true)]
+    Stations {
+        #[command(subcommand)]
+        action: Option<StationsCommand>,
 
-        /// Electricity areas to fetch (comma-separated: NO1,NO2,...). Defaults to all.
+        /// Filter to specific electricity areas (comma-separated: NO1,NO2,...). Defaults to all.
         #[arg(long, value_delimiter = ',')]
         areas: Option<Vec<ElArea>>,
 
-        /// Output destination: databricks or csv
-        #[arg(long, default_value = "databricks")]
-        output: Output,
+        /// Only show currently active stations
+        #[arg(long, default_value = "true")]
+        active_only: bool,
+    },
+
+    /// Apply pending schema migrations to the Databricks raw tables
+    Migrate {
+        /// List applied and pending migrations without applying anything
+        #[arg(long)]
+        status: bool,
+    },
+
+    /// Undo an ingest run: delete its rows and restore what it replaced (Delta time travel)
+    Rollback {
+        /// Run ID from the run summary or the ingest_runs table
+        #[arg(long)]
+        run_id: String,
 
-        /// CSV output file path (used when --output csv)
-        #[arg(long, default_value = "precipitation.csv")]
-        csv_path: PathBuf,
+        /// Roll back even if a later run reloaded part of the same date range
+        #[arg(long)]
+        force: bool,
 
-        /// Fetch in parallel: one thread per station per year
+        /// Print the rollback statements without running them
         #[arg(long)]
-        parallel: bool,
+        dry_run: bool,
     },
+}
 
-    /// List available precipitation weather stations from the Frost API
-    Stations {
-        /// Filter to specific electricity areas (comma-separated: NO1,NO2,...). Defaults to all.
+#[derive(Args)]
+struct IngestArgs {
+    /// Start date (inclusive), e.g. 2024-01-01
+    #[arg(long)]
+    from: String,
+
+    /// End date (exclusive), e.g. 2024-02-01
+    #[arg(long)]
+    to: String,
+
+    /// Electricity areas to fetch (comma-separated: NO1,NO2,...). Defaults to all.
+    #[arg(long, value_delimiter = ',')]
+    areas: Option<Vec<ElArea>>,
+
+    /// Output destination: databricks or csv
+    #[arg(long, default_value = "databricks")]
+    output: Output,
+
+    /// CSV output file path (used when --output csv)
+    #[arg(long, default_value = "precipitation.csv")]
+    csv_path: PathBuf,
+
+    /// Fetch in parallel: one thread per station per year
+    #[arg(long)]
+    parallel: bool,
+
+    /// Station catalogue file (from `stations suggest`) to use instead of the built-in list
+    #[arg(long)]
+    stations_file: Option<PathBuf>,
+
+    /// Skip the availableTimeSeries check and request every station/period
+    #[arg(long)]
+    no_preflight: bool,
+
+    /// Write a JSON run summary to this path (`-` for stdout), also on failure
+    #[arg(long)]
+    summary_json: Option<PathBuf>,
+
+    /// Print the stations, chunk plan, estimated requests and SQL, then exit without
+    /// calling Frost or Databricks
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Above this many rows, load through a volume upload and COPY INTO instead of INSERTs
+    #[arg(long, default_value_t = 50_000)]
+    bulk_load_threshold: usize,
+
+    /// INSERT batches to run against the warehouse at once
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    db_concurrency: usize,
+
+    /// Stop the SQL warehouse when the run ends, whether it succeeded or not
+    #[arg(long)]
+    stop_warehouse_after: bool,
+
+    /// Fail the run if the row counts stored per station don't match what was loaded
+    /// (they are always checked and reported in the run summary)
+    #[arg(long)]
+    verify: bool,
+
+    /// Also write a gap-filled series, with missing station-days estimated from
+    /// neighbouring stations, to a separate table (or CSV). The raw data is unchanged.
+    #[arg(long)]
+    fill_gaps: bool,
+
+    /// Gap-filled CSV output path (used with --fill-gaps and --output csv)
+    #[arg(long, default_value = "precipitation_gap_filled.csv")]
+    gap_filled_csv_path: PathBuf,
+}
+
+#[derive(Subcommand)]
+enum StationsCommand {
+    /// Score candidate stations on coverage, activity and spread, and write a station catalogue
+    Suggest {
+        /// Electricity areas to pick stations for (comma-separated: NO1,NO2,...). Defaults to all.
         #[arg(long, value_delimiter = ',')]
         areas: Option<Vec<ElArea>>,
 
-        /// Only show currently active stations
-        #[arg(long, default_value = "true")]
-        active_only: bool,
+        /// Stations to pick per area
+        #[arg(long, default_value_t = 5)]
+        count: usize,
+
+        /// Start of the period to score coverage over, e.g. 2015-01-01
+        #[arg(long)]
+        from: String,
+
+        /// End of the period (exclusive). Defaults to today.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Where to write the station catalogue
+        #[arg(long, default_value = "stations.json")]
+        output: PathBuf,
     },
 }
 
@@ -69,13 +257,85 @@ enum Output {
     Csv,
 }
 
+impl Output {
+    fn name(&self) -> &'static str {
+        match self {
+            Output::Databricks => "databricks",
+            Output::Csv => "csv",
+        }
+    }
+}
+
+const ALL_AREAS: [ElArea; 5] = [
+    ElArea::NO1,
+    ElArea::NO2,
+    ElArea::NO3,
+    ElArea::NO4,
+    ElArea::NO5,
+];
+
+/// True unless the pre-flight check says there is nothing to fetch for this station/period.
+fn may_have_data(
+    availability: Option<&Availability>,
+    station: &Station,
+    from: &str,
+    to: &str,
+) -> bool {
+    let Some(availability) = availability else {
+        return true;
+    };
+    match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => availability.has_data(station.id, from, to),
+        _ => true,
+    }
+}
+
 fn fetch_sequential(
     frost: &FrostClient,
     stations: &[&Station],
     from: &str,
     to: &str,
+    availability: Option<&Availability>,
+    counts: &mut ChunkCounts,
+) -> Result<Vec<PrecipitationRow>> {
+    let requested: Vec<&Station> = stations
+        .iter()
+        .copied()
+        .filter(|s| may_have_data(availability, s, from, to))
+        .collect();
+    counts.skipped += stations.len() - requested.len();
+    if requested.is_empty() {
+        return Ok(Vec::new());
+    }
+    count_outcome(frost.fetch_precipitation(&requested, from, to), counts)
+}
+
+/// Fold one Frost request's result into the chunk counts.
+/// "No data" is an expected outcome for a station/period, not a failure of the run.
+fn count_outcome(
+    result: Result<Vec<PrecipitationRow>>,
+    counts: &mut ChunkCounts,
 ) -> Result<Vec<PrecipitationRow>> {
-    frost.fetch_precipitation(stations, from, to)
+    counts.attempted += 1;
+    match result {
+        Ok(rows) if rows.is_empty() => {
+            counts.empty += 1;
+            Ok(rows)
+        }
+        Ok(rows) => {
+            counts.succeeded += 1;
+            Ok(rows)
+        }
+        Err(FrostCliError::NoData { context }) => {
+            warn!(%context, "No data available (skipping)");
+            counts.empty += 1;
+            Ok(Vec::new())
+        }
+        Err(e) => {
+            counts.failed += 1;
+            Err(e)
+        }
+    }
 }
 
 /// Simple counting semaphore using stdlib primitives.
@@ -114,6 +374,8 @@ fn fetch_parallel(
     stations: &[&'static Station],
     from: &str,
     to: &str,
+    availability: Option<&Availability>,
+    counts: &mut ChunkCounts,
 ) -> Result<Vec<PrecipitationRow>> {
     let chunks = yearly_chunks(from, to)?;
 
@@ -127,34 +389,60 @@ fn fetch_parallel(
         })
         .collect();
 
+    let planned = work.len();
+    let work: Vec<_> = work
+        .into_iter()
+        .filter(|(station, f, t)| may_have_data(availability, station, f, t))
+        .collect();
+    counts.skipped += planned - work.len();
+
     info!(
         tasks = work.len(),
+        skipped = planned - work.len(),
         stations = stations.len(),
         chunks = chunks.len(),
         max_concurrent = MAX_CONCURRENT_REQUESTS,
         "Parallel fetch"
     );
 
+    let progress = Progress::new(work.len());
+    let progress_ref = &progress;
     let semaphore = Semaphore::new(MAX_CONCURRENT_REQUESTS);
     let all_rows: Mutex<Vec<PrecipitationRow>> = Mutex::new(Vec::new());
-    let errors: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    let errors: Mutex<Vec<FrostCliError>> = Mutex::new(Vec::new());
+    let task_counts: Mutex<ChunkCounts> = Mutex::new(ChunkCounts::default());
     let sem_ref = &semaphore;
     let all_rows_ref = &all_rows;
     let errors_ref = &errors;
+    let task_counts_ref = &task_counts;
+    let parent = tracing::Span::current();
+    let parent_ref = &parent;
 
     thread::scope(|s| {
         for (station, chunk_from, chunk_to) in &work {
             s.spawn(move || {
+                // Spans are per-thread; link each task to the ingest span explicitly
+                let span = info_span!(
+                    parent: parent_ref,
+                    "fetch_chunk",
+                    station = station.id,
+                    chunk = %format_args!("{chunk_from}/{chunk_to}"),
+                );
+                let _enter = span.enter();
+
                 sem_ref.acquire();
                 let single = &[*station];
                 let result = frost.fetch_precipitation(single, chunk_from, chunk_to);
                 sem_ref.release();
+                let result = count_outcome(result, &mut task_counts_ref.lock().unwrap());
 
                 match result {
                     Ok(rows) => {
+                        progress_ref.task_done(rows.len(), false);
                         all_rows_ref.lock().unwrap().extend(rows);
                     }
                     Err(e) => {
+                        progress_ref.task_done(0, true);
                         error!(
                             station = station.id,
                             from = %chunk_from,
@@ -162,23 +450,19 @@ fn fetch_parallel(
                             error = %e,
                             "Fetch failed"
                         );
-                        errors_ref.lock().unwrap().push(format!(
-                            "{} {chunk_from}..{chunk_to}: {e}",
-                            station.id
-                        ));
+                        errors_ref.lock().unwrap().push(e);
                     }
                 }
             });
         }
     });
 
+    drop(progress);
+    counts.add(&task_counts.into_inner().unwrap());
+
     let errs = errors.into_inner().unwrap();
     if !errs.is_empty() {
-        return Err(error::FrostCliError::config(format!(
-            "{} fetch(es) failed:\n  {}",
-            errs.len(),
-            errs.join("\n  ")
-        )));
+        return Err(FrostCliError::FetchFailed(errs));
     }
 
     let rows = all_rows.into_inner().unwrap();
@@ -186,25 +470,101 @@ fn fetch_parallel(
     Ok(rows)
 }
 
-fn run_ingest(
-    from: String,
-    to: String,
-    areas: Option<Vec<ElArea>>,
-    output: Output,
-    csv_path: PathBuf,
-    parallel: bool,
-) -> Result<()> {
-    let config = config::Config::load()?;
+fn run_ingest(args: IngestArgs, config_options: &ConfigOptions) -> Result<()> {
+    if args.dry_run {
+        return dry_run(&args, config_options);
+    }
 
-    let areas: Vec<ElArea> = areas
-        .unwrap_or_else(|| vec![ElArea::NO1, ElArea::NO2, ElArea::NO3, ElArea::NO4, ElArea::NO5]);
+    let mut summary = RunSummary::start(RunParameters {
+        from: args.from.clone(),
+        to: args.to.clone(),
+        areas: args
+            .areas
+            .as_deref()
+            .unwrap_or(&ALL_AREAS)
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        output: args.output.name().to_string(),
+        parallel: args.parallel,
+        preflight: !args.no_preflight,
+        stations_file: args.stations_file.as_ref().map(|p| p.display().to_string()),
+    });
 
-    let stations = stations_for_areas(&areas);
-    if stations.is_empty() {
-        return Err(error::FrostCliError::config(
-            "No stations matched the selected areas",
-        ));
+    let span = info_span!("ingest", run_id = %summary.run_id, from = %args.from, to = %args.to);
+    let mut db = None;
+    let result = span.in_scope(|| ingest(&args, config_options, &mut summary, &mut db));
+    summary.finish(&result);
+
+    // Best effort: the rows already carry the run ID, so a missing audit row loses little
+    if let Some(db) = &db
+        && let Err(e) = span.in_scope(|| record_run(db, &summary))
+    {
+        warn!(run_id = %summary.run_id, error = %e, "Could not record run in the audit table");
+    }
+
+    if args.stop_warehouse_after
+        && let Some(db) = &db
+        && let Err(e) = span.in_scope(|| db.stop_warehouse())
+    {
+        warn!(warehouse = %db.warehouse_id(), error = %e, "Could not stop SQL warehouse");
+    }
+
+    if let Some(path) = &args.summary_json
+        && let Err(e) = summary.write(path)
+    {
+        error!(error = %e, "Could not write run summary");
+        // Keep the ingest error if both failed; the summary is secondary
+        return result.and(Err(e));
+    }
+
+    result
+}
+
+/// Record the run in the audit table. A run that stopped before loading (no data,
+/// everything rejected, a failed fetch) hasn't migrated yet, so `ingest_runs` may
+/// not exist on a fresh target; migrate first in that case.
+fn record_run(db: &DatabricksClient, summary: &RunSummary) -> Result<()> {
+    // Set by load_databricks right after it migrates
+    if summary.table_version_before.is_none() {
+        db.wait_for_warehouse()?;
+        migrations::migrate(db)?;
+    }
+    runs::record(db, summary)
+}
+
+/// Run the ingest. `db_slot` receives the Databricks client once it's configured,
+/// so the caller can record the run's outcome with it, including on failure.
+fn ingest(
+    args: &IngestArgs,
+    config_options: &ConfigOptions,
+    summary: &mut RunSummary,
+    db_slot: &mut Option<DatabricksClient>,
+) -> Result<()> {
+    let IngestArgs {
+        from,
+        to,
+        areas,
+        output,
+        csv_path,
+        parallel,
+        stations_file,
+        ..
+    } = args;
+    // Check every section this run needs before spending time on the fetch
+    let config = config::Config::load(config_options)?;
+    let frost_config = config.frost()?;
+    let rules = config.validation()?;
+    let outlier_settings = config.outliers()?;
+    let gap_fill_settings = config.gap_fill()?;
+    if let Output::Databricks = output {
+        *db_slot = Some(DatabricksClient::new(&config.databricks()?));
     }
+    let db = db_slot.as_ref();
+
+    let areas: Vec<ElArea> = areas.clone().unwrap_or_else(|| ALL_AREAS.to_vec());
+    let stations = resolve_stations(&areas, stations_file.as_deref())?;
+    summary.stations = stations.iter().map(|s| s.id.to_string()).collect();
 
     let area_str = areas
         .iter()
@@ -221,35 +581,73 @@ fn run_ingest(
         "Starting Frost CLI ingest"
     );
 
-    let frost = FrostClient::new(config.frost_client_id.clone());
-    let rows = if parallel {
-        fetch_parallel(&frost, &stations, &from, &to)?
-    } else {
-        fetch_sequential(&frost, &stations, &from, &to)?
-    };
+    // Starting takes minutes; let it come up while we fetch
+    if let Some(db) = db {
+        db.start_warehouse()?;
+    }
+
+    let frost = FrostClient::new(&frost_config);
+    let fetched = fetch(&frost, &stations, args, summary);
+    summary.requests.frost = frost.request_count();
+    let rows = fetched?;
+    summary.record_fetched(&rows);
 
     if rows.is_empty() {
         info!("No precipitation data returned. Nothing to do.");
         return Ok(());
     }
 
-    match output {
-        Output::Csv => {
-            let written = csv_writer::write_csv(&rows, &csv_path)?;
+    let (rows, removed) = dedup::dedup(rows);
+    summary.duplicates_removed = removed;
+
+    let (mut rows, report) = validation::validate(rows, &rules, from, to);
+    let failed = report.failed_rules();
+    summary.validation = Some(report);
+    if !failed.is_empty() {
+        return Err(FrostCliError::Validation(format!(
+            "rule(s) set to fail were violated: {}",
+            failed.join(", ")
+        )));
+    }
+    if rows.is_empty() {
+        info!("Every fetched row was rejected by validation. Nothing to write.");
+        return Ok(());
+    }
+
+    summary.outliers = Some(outliers::detect(&mut rows, &outlier_settings));
+
+    let gap_filled = if args.fill_gaps {
+        let (filled, report) = gap_fill::fill_gaps(
+            &rows,
+            &stations,
+            parse_date(from)?,
+            parse_date(to)?,
+            &gap_fill_settings,
+        );
+        summary.gap_fill = Some(report);
+        Some(filled)
+    } else {
+        None
+    };
+
+    match db {
+        None => {
+            let written = csv_writer::write_csv(&rows, csv_path)?;
+            summary.record_written("csv", written);
             info!(rows = written, path = %csv_path.display(), "Done — wrote CSV");
+            if let (Some(filled), Some(report)) = (&gap_filled, &mut summary.gap_fill) {
+                let path = &args.gap_filled_csv_path;
+                report.rows_written = csv_writer::write_gap_filled_csv(filled, path)?;
+                info!(rows = report.rows_written, path = %path.display(), "Wrote gap-filled CSV");
+            }
         }
-        Output::Databricks => {
-            let db = DatabricksClient::new(&config);
-            db.create_schema()?;
-            db.create_table()?;
-            db.delete_existing(&from, &to)?;
-            let inserted = db.insert_precipitation(&rows)?;
+        Some(db) => {
+            let loaded = load_databricks(db, &rows, gap_filled.as_deref(), args, summary);
+            summary.requests.databricks_statements = db.statement_count();
+            let inserted = loaded?;
             info!(
                 rows = inserted,
-                table = format_args!(
-                    "{}.raw_frost.precipitation",
-                    config.databricks_catalog
-                ),
+                table = %db.table_name(),
                 "Done — inserted into Databricks"
             );
         }
@@ -258,9 +656,201 @@ fn run_ingest(
     Ok(())
 }
 
-fn run_stations(areas: Option<Vec<ElArea>>, active_only: bool) -> Result<()> {
-    let config = config::Config::load_frost_only()?;
-    let frost = FrostClient::new(config.frost_client_id);
+/// Stations to ingest: from the catalogue file if given, else the built-in list.
+fn resolve_stations(
+    areas: &[ElArea],
+    stations_file: Option<&std::path::Path>,
+) -> Result<Vec<&'static Station>> {
+    let stations: Vec<&'static Station> = match stations_file {
+        Some(path) => StationCatalogue::read(path)?
+            .into_stations()?
+            .into_iter()
+            .filter(|s| areas.contains(&s.el_area))
+            .collect(),
+        None => stations_for_areas(areas),
+    };
+    if stations.is_empty() {
+        return Err(error::FrostCliError::config(
+            "No stations matched the selected areas",
+        ));
+    }
+    Ok(stations)
+}
+
+/// `ingest --dry-run`: resolve everything a run needs and print it, without network calls.
+fn dry_run(args: &IngestArgs, config_options: &ConfigOptions) -> Result<()> {
+    let config = config::Config::load(config_options)?;
+    config.frost()?;
+    let validation = config.validation()?;
+    let outliers = config.outliers()?;
+    let gap_fill = config.gap_fill()?;
+    let areas: Vec<ElArea> = args.areas.clone().unwrap_or_else(|| ALL_AREAS.to_vec());
+    let stations = resolve_stations(&areas, args.stations_file.as_deref())?;
+
+    // Constructing the client only builds the HTTP client; nothing is sent
+    let db;
+    let target = match args.output {
+        Output::Csv => plan::PlanTarget::Csv(&args.csv_path, &args.gap_filled_csv_path),
+        Output::Databricks => {
+            db = DatabricksClient::new(&config.databricks()?);
+            plan::PlanTarget::Databricks(&db)
+        }
+    };
+
+    plan::IngestPlan {
+        from: &args.from,
+        to: &args.to,
+        stations: &stations,
+        parallel: args.parallel,
+        preflight: !args.no_preflight,
+        bulk_load_threshold: args.bulk_load_threshold,
+        db_concurrency: args.db_concurrency,
+        stop_warehouse_after: args.stop_warehouse_after,
+        verify: args.verify,
+        validation: &validation,
+        outliers: &outliers,
+        gap_fill: args.fill_gaps.then_some(&gap_fill),
+        target,
+    }
+    .print()
+}
+
+fn fetch(
+    frost: &FrostClient,
+    stations: &[&'static Station],
+    args: &IngestArgs,
+    summary: &mut RunSummary,
+) -> Result<Vec<PrecipitationRow>> {
+    let (from, to) = (&args.from, &args.to);
+
+    let availability = if args.no_preflight {
+        None
+    } else {
+        // Advisory only: if the check itself fails, fall back to requesting everything
+        availability::preflight(frost, stations, from, to)
+            .inspect_err(|e| warn!(error = %e, "Pre-flight availability check failed (continuing without it)"))
+            .ok()
+    };
+
+    let availability = availability.as_ref();
+    if args.parallel {
+        fetch_parallel(frost, stations, from, to, availability, &mut summary.chunks)
+    } else {
+        fetch_sequential(frost, stations, from, to, availability, &mut summary.chunks)
+    }
+}
+
+fn load_databricks(
+    db: &DatabricksClient,
+    rows: &[PrecipitationRow],
+    gap_filled: Option<&[gap_fill::FilledRow]>,
+    args: &IngestArgs,
+    summary: &mut RunSummary,
+) -> Result<usize> {
+    db.wait_for_warehouse()?;
+    migrations::migrate(db)?;
+    // Recorded with the run so `rollback` can restore what the DELETE removes
+    summary.table_version_before = Some(db.table_version()?);
+    db.delete_existing(&args.from, &args.to)?;
+    let inserted = if rows.len() > args.bulk_load_threshold {
+        db.bulk_load_precipitation(rows, &summary.run_id)
+    } else {
+        db.insert_precipitation(rows, &summary.run_id, args.db_concurrency)
+    }?;
+    summary.record_written("databricks", inserted);
+    verify_load(db, rows, args, summary)?;
+    if let (Some(filled), Some(report)) = (gap_filled, &mut summary.gap_fill) {
+        // Recorded with the run so `rollback` can restore the gap-filled range too
+        summary.gap_filled_version_before = Some(db.gap_filled_table_version()?);
+        report.rows_written = db.write_gap_filled(
+            filled,
+            &args.from,
+            &args.to,
+            &summary.run_id,
+            args.db_concurrency,
+        )?;
+    }
+    Ok(inserted)
+}
+
+/// Reconcile stored row counts with the rows just loaded and record the result.
+/// Mismatches, or a failed check, only warn unless `--verify` is set.
+fn verify_load(
+    db: &DatabricksClient,
+    rows: &[PrecipitationRow],
+    args: &IngestArgs,
+    summary: &mut RunSummary,
+) -> Result<()> {
+    let result = reconcile::reconcile(db, &summary.run_id, &args.from, &args.to, rows);
+    let reconciliation = match result {
+        Ok(r) => r,
+        Err(e) if args.verify => return Err(e),
+        Err(e) => {
+            warn!(error = %e, "Could not reconcile stored rows");
+            return Ok(());
+        }
+    };
+    let consistent = reconciliation.is_consistent();
+    let message = format!(
+        "{} station(s) differ: {} rows stored by this run, {} loaded; \
+         {} rows stored in range, {} loaded",
+        reconciliation.mismatches.len(),
+        reconciliation.rows_stored,
+        reconciliation.rows_expected,
+        reconciliation.rows_stored_in_range,
+        reconciliation.rows_expected_in_range,
+    );
+    summary.reconciliation = Some(reconciliation);
+    if !consistent && args.verify {
+        return Err(FrostCliError::Verification(message));
+    }
+    Ok(())
+}
+
+fn run_migrate(config_options: &ConfigOptions, status_only: bool) -> Result<()> {
+    let config = config::Config::load(config_options)?;
+    let db = DatabricksClient::new(&config.databricks()?);
+    db.wait_for_warehouse()?;
+
+    if !status_only {
+        let applied = migrations::migrate(&db)?;
+        info!(applied, table = %db.table_name(), "Migrations complete");
+        return Ok(());
+    }
+
+    println!("Migrations for {}:", db.table_name());
+    for s in migrations::status(&db)? {
+        let state = match &s.applied_at {
+            Some(at) => format!("applied {at}"),
+            None => "pending".to_string(),
+        };
+        println!(
+            "  {:>3}  {:<32} {}",
+            s.migration.version, state, s.migration.description
+        );
+    }
+    Ok(())
+}
+
+fn run_rollback(
+    config_options: &ConfigOptions,
+    run_id: &str,
+    force: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let config = config::Config::load(config_options)?;
+    let db = DatabricksClient::new(&config.databricks()?);
+    db.wait_for_warehouse()?;
+    runs::rollback(&db, run_id, force, dry_run)
+}
+
+fn run_stations(
+    config_options: &ConfigOptions,
+    areas: Option<Vec<ElArea>>,
+    active_only: bool,
+) -> Result<()> {
+    let config = config::Config::load(config_options)?;
+    let frost = FrostClient::new(&config.frost()?);
 
     let all_stations = frost.list_precipitation_stations()?;
 
@@ -305,8 +895,12 @@ fn run_stations(areas: Option<Vec<ElArea>>, active_only: bool) -> Result<()> {
         }
 
         let active_marker = if station.is_active { " " } else { "*" };
+        let position = match (station.latitude, station.longitude) {
+            (Some(lat), Some(lon)) => format!("{lat:.3},{lon:.3}"),
+            _ => "-".to_string(),
+        };
         println!(
-            " {active_marker} {id:<12} {name:<42} {county:<25} {muni}",
+            " {active_marker} {id:<12} {name:<42} {county:<25} {muni:<20} {position}",
             id = station.id,
             name = station.name,
             county = station.county,
@@ -327,29 +921,129 @@ fn run_stations(areas: Option<Vec<ElArea>>, active_only: bool) -> Result<()> {
     Ok(())
 }
 
-fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
+fn run_suggest(
+    config_options: &ConfigOptions,
+    areas: Option<Vec<ElArea>>,
+    count: usize,
+    from: String,
+    to: Option<String>,
+    output: PathBuf,
+) -> Result<()> {
+    let config = config::Config::load(config_options)?;
+    let frost = FrostClient::new(&config.frost()?);
+
+    let areas: Vec<ElArea> = areas.unwrap_or_else(|| ALL_AREAS.to_vec());
+    let to = to.unwrap_or_else(|| chrono::Local::now().date_naive().to_string());
+
+    let catalogue = suggest::suggest_stations(&frost, &areas, count, &from, &to)?;
+
+    let mut current_area: Option<&str> = None;
+    for station in &catalogue.stations {
+        if current_area != Some(station.el_area.as_str()) {
+            println!("===== {} =====", station.el_area);
+            current_area = Some(&station.el_area);
+        }
+        let Some(sel) = &station.selection else {
+            continue;
+        };
+        let nearest = sel
+            .nearest_selected_km
+            .map_or("-".to_string(), |km| format!("{km:.0} km"));
+        println!(
+            "   {id:<12} {name:<42} score {score:.3}  coverage {cov:>5.1}%  {active:<8} nearest {nearest}",
+            id = station.id,
+            name = station.name,
+            score = sel.score,
+            cov = sel.coverage * 100.0,
+            active = if sel.active { "active" } else { "inactive" },
+        );
+    }
+
+    for &area in &areas {
+        let picked = catalogue
+            .stations
+            .iter()
+            .filter(|s| s.el_area == area.to_string())
+            .count();
+        if picked < count {
+            println!("\n{area}: only {picked} of {count} stations have data in {from}..{to}");
+        }
+    }
+
+    catalogue.write(&output)?;
+    println!(
+        "\nWrote {} stations to {}",
+        catalogue.stations.len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let telemetry_options = telemetry::TelemetryOptions {
+        otlp_endpoint: cli.otel_endpoint.clone(),
+        file: cli.otel_file.clone(),
+    };
+    let (_telemetry, otel_layer) = match telemetry::init(&telemetry_options) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+
+    let log_layer = match logging::layer(cli.log_format, cli.log_file.as_deref()) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: could not open log file: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+
+    tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
-        .with_target(false)
+        .with(log_layer)
+        .with(otel_layer)
         .init();
 
-    let cli = Cli::parse();
-
-    match cli.command {
-        Command::Ingest {
-            from,
-            to,
-            areas,
-            output,
-            csv_path,
-            parallel,
-        } => run_ingest(from, to, areas, output, csv_path, parallel),
+    let config_options = cli.config_options();
+    let result = match cli.command {
+        Command::Ingest(args) => run_ingest(args, &config_options),
+        Command::Stations {
+            action:
+                Some(StationsCommand::Suggest {
+                    areas,
+                    count,
+                    from,
+                    to,
+                    output,
+                }),
+            ..
+        } => run_suggest(&config_options, areas, count, from, to, output),
         Command::Stations {
+            action: None,
             areas,
             active_only,
-        } => run_stations(areas, active_only),
+        } => run_stations(&config_options, areas, active_only),
+        Command::Migrate { status } => run_migrate(&config_options, status),
+        Command::Rollback {
+            run_id,
+            force,
+            dry_run,
+        } => run_rollback(&config_options, &run_id, force, dry_run),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::from(e.exit_code())
+        }
     }
 }
@@ -0,0 +1,280 @@
+use crate::databricks_client::DatabricksClient;
+use crate::error::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Table in the raw schema recording which migrations have run, per target table.
+const MIGRATIONS_TABLE: &str = "_frost_migrations";
+
+/// One schema change to the raw tables, applied at most once per target table.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    /// DDL with `{schema}`, `{table}`, `{volume}` and `{gap_filled_table}` standing
+    /// for the qualified schema, precipitation table, staging volume and gap-filled
+    /// table names.
+    ///
+    /// `ALTER TABLE ... ADD COLUMNS` statements are skipped when their first column
+    /// already exists: the migration may have run without being recorded, or another
+    /// target table's migrations may have changed a table they share.
+    pub statements: &'static [&'static str],
+}
+
+/// All migrations, in the order they are applied. Append only: never edit or
+/// renumber a migration that has been released.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create precipitation table",
+        statements: &[r#"CREATE TABLE IF NOT EXISTS {table} (
+    station_id       STRING  NOT NULL,
+    station_name     STRING  NOT NULL,
+    el_area          STRING  NOT NULL,
+    reference_time   DATE    NOT NULL,
+    precipitation_mm DOUBLE,
+    quality_code     INT,
+    ingested_at      TIMESTAMP
+)"#],
+    },
+    Migration {
+        version: 2,
+        description: "Add run lineage columns",
+        statements: &["ALTER TABLE {table} ADD COLUMNS (ingest_run_id STRING, cli_version STRING)"],
+    },
+    Migration {
+        version: 3,
+        description: "Create ingest_runs audit table",
+        statements: &[r#"CREATE TABLE IF NOT EXISTS {schema}.ingest_runs (
+    run_id                STRING    NOT NULL,
+    table_name            STRING    NOT NULL,
+    cli_version           STRING,
+    status                STRING    NOT NULL,
+    started_at            TIMESTAMP,
+    finished_at           TIMESTAMP,
+    duration_secs         DOUBLE,
+    from_date             DATE,
+    to_date               DATE,
+    parameters            STRING,
+    stations              STRING,
+    rows_fetched          BIGINT,
+    rows_written          BIGINT,
+    frost_requests        BIGINT,
+    databricks_statements BIGINT,
+    error                 STRING
+)"#],
+    },
+    Migration {
+        version: 4,
+        description: "Record table version before each run",
+        statements: &["ALTER TABLE {schema}.ingest_runs ADD COLUMNS (table_version_before BIGINT)"],
+    },
+    Migration {
+        version: 5,
+        description: "Create staging volume for bulk loads",
+        statements: &["CREATE VOLUME IF NOT EXISTS {volume}"],
+    },
+    Migration {
+        version: 6,
+        description: "Add validation flags column",
+        statements: &["ALTER TABLE {table} ADD COLUMNS (validation_flags STRING)"],
+    },
+    Migration {
+        version: 7,
+        description: "Add spatial outlier columns",
+        statements: &[
+            "ALTER TABLE {table} ADD COLUMNS (spatial_z DOUBLE, spatial_outlier BOOLEAN)",
+        ],
+    },
+    Migration {
+        version: 8,
+        description: "Create gap-filled precipitation table",
+        statements: &[r#"CREATE TABLE IF NOT EXISTS {gap_filled_table} (
+    station_id        STRING  NOT NULL,
+    station_name      STRING  NOT NULL,
+    el_area           STRING  NOT NULL,
+    reference_time    DATE    NOT NULL,
+    precipitation_mm  DOUBLE  NOT NULL,
+    imputed           BOOLEAN NOT NULL,
+    imputation_method STRING,
+    donor_stations    INT,
+    ingested_at       TIMESTAMP,
+    ingest_run_id     STRING,
+    cli_version       STRING
+)"#],
+    },
+    Migration {
+        version: 9,
+        description: "Record gap-filled table version before each run",
+        statements: &[
+            "ALTER TABLE {schema}.ingest_runs ADD COLUMNS (gap_filled_version_before BIGINT)",
+        ],
+    },
+];
+
+impl Migration {
+    /// The statements with placeholders filled in for `db`'s target.
+    pub fn render(&self, db: &DatabricksClient) -> Vec<String> {
+        let (schema, table, volume) = (db.schema_name(), db.table_name(), db.volume_name());
+        let gap_filled_table = db.gap_filled_table_name();
+        self.statements
+            .iter()
+            .map(|sql| {
+                sql.replace("{schema}", &schema)
+                    .replace("{table}", &table)
+                    .replace("{volume}", &volume)
+                    .replace("{gap_filled_table}", &gap_filled_table)
+            })
+            .collect()
+    }
+}
+
+/// For `ALTER TABLE <table> ADD COLUMNS (<column> ...)`, the table and the first
+/// column added.
+pub fn added_column(sql: &str) -> Option<(&str, &str)> {
+    let (table, columns) = sql
+        .strip_prefix("ALTER TABLE ")?
+        .split_once(" ADD COLUMNS (")?;
+    Some((table, columns.split_whitespace().next()?))
+}
+
+/// Whether `table`, a fully qualified name, exists.
+fn table_exists(db: &DatabricksClient, table: &str) -> Result<bool> {
+    let [catalog, schema, table] = table.split('.').collect::<Vec<_>>()[..] else {
+        return Ok(false);
+    };
+
+    let sql = format!(
+        "SELECT COUNT(*) AS found FROM {catalog}.information_schema.tables \
+         WHERE lower(table_schema) = lower('{schema}') \
+         AND lower(table_name) = lower('{table}')"
+    );
+    any_rows(db, &sql)
+}
+
+/// Whether `column` exists in `table`, a fully qualified name.
+fn column_exists(db: &DatabricksClient, table: &str, column: &str) -> Result<bool> {
+    let [catalog, schema, table] = table.split('.').collect::<Vec<_>>()[..] else {
+        return Ok(false);
+    };
+
+    let sql = format!(
+        "SELECT COUNT(*) AS found FROM {catalog}.information_schema.columns \
+         WHERE lower(table_schema) = lower('{schema}') \
+         AND lower(table_name) = lower('{table}') \
+         AND lower(column_name) = lower('{column}')"
+    );
+    any_rows(db, &sql)
+}
+
+/// Whether a `SELECT COUNT(*) AS found ...` query found anything.
+fn any_rows(db: &DatabricksClient, sql: &str) -> Result<bool> {
+    #[derive(Deserialize)]
+    struct Found {
+        found: i64,
+    }
+
+    let rows: Vec<Found> = db.query(sql)?;
+    Ok(rows.first().is_some_and(|r| r.found > 0))
+}
+
+/// A migration and when it was applied to the current target, if it was.
+pub struct MigrationStatus {
+    pub migration: &'static Migration,
+    pub applied_at: Option<String>,
+}
+
+/// Applied/pending state of every known migration for `db`'s target table.
+///
+/// Read-only: without the bookkeeping table (a new schema), every migration is pending.
+pub fn status(db: &DatabricksClient) -> Result<Vec<MigrationStatus>> {
+    #[derive(Deserialize)]
+    struct Applied {
+        version: u32,
+        applied_at: Option<String>,
+    }
+
+    let bookkeeping = format!("{}.{MIGRATIONS_TABLE}", db.schema_name());
+    let rows: Vec<Applied> = if table_exists(db, &bookkeeping)? {
+        db.query(&format!(
+            "SELECT version, CAST(applied_at AS STRING) AS applied_at FROM {bookkeeping} \
+             WHERE table_name = '{table}'",
+            table = db.table_name(),
+        ))?
+    } else {
+        Vec::new()
+    };
+    let applied: BTreeMap<u32, String> = rows
+        .into_iter()
+        .map(|row| (row.version, row.applied_at.unwrap_or_default()))
+        .collect();
+
+    Ok(MIGRATIONS
+        .iter()
+        .map(|m| MigrationStatus {
+            migration: m,
+            applied_at: applied.get(&m.version).cloned(),
+        })
+        .collect())
+}
+
+/// Apply pending migrations in version order. Returns how many were applied.
+///
+/// Each migration is recorded right after its statements succeed, so a failed run
+/// resumes from the migration that failed.
+pub fn migrate(db: &DatabricksClient) -> Result<usize> {
+    db.create_schema()?;
+    db.execute(&format!(
+        "CREATE TABLE IF NOT EXISTS {schema}.{MIGRATIONS_TABLE} (
+    table_name  STRING NOT NULL,
+    version     INT    NOT NULL,
+    description STRING,
+    applied_at  TIMESTAMP
+)",
+        schema = db.schema_name()
+    ))?;
+
+    let pending: Vec<&Migration> = status(db)?
+        .into_iter()
+        .filter(|s| s.applied_at.is_none())
+        .map(|s| s.migration)
+        .collect();
+
+    if pending.is_empty() {
+        info!(table = %db.table_name(), "Schema is up to date");
+        return Ok(0);
+    }
+
+    for migration in &pending {
+        info!(
+            version = migration.version,
+            description = migration.description,
+            table = %db.table_name(),
+            "Applying migration"
+        );
+        for sql in migration.render(db) {
+            if let Some((table, column)) = added_column(&sql)
+                && column_exists(db, table, column)?
+            {
+                info!(
+                    version = migration.version,
+                    table = %table,
+                    column = %column,
+                    "Column already exists, skipping statement"
+                );
+                continue;
+            }
+            db.execute(&sql)?;
+        }
+        db.execute(&format!(
+            "INSERT INTO {schema}.{MIGRATIONS_TABLE} (table_name, version, description, applied_at) \
+             VALUES ('{table}', {version}, '{description}', CURRENT_TIMESTAMP())",
+            schema = db.schema_name(),
+            table = db.table_name(),
+            version = migration.version,
+            description = migration.description.replace('\'', "''"),
+        ))?;
+    }
+
+    Ok(pending.len())
+}
@@ -0,0 +1,125 @@
+use crate::frost_client::PrecipitationRow;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Scales the median absolute deviation to the standard deviation of a normal
+/// distribution (Iglewicz and Hoaglin's modified z-score).
+const MAD_SCALE: f64 = 0.6745;
+
+/// Flagged station-days listed in the report; the rest are only counted.
+const MAX_EXAMPLES: usize = 10;
+
+/// Thresholds for the spatial consistency check.
+#[derive(Debug, Clone)]
+pub struct OutlierSettings {
+    /// A station-day is an outlier when its robust z-score exceeds this in absolute value.
+    pub threshold: f64,
+    /// Other stations in the area that need a value that day for the check to run.
+    pub min_neighbours: usize,
+    /// Lower bound for the MAD, in mm. On dry days most stations report 0 and the
+    /// MAD is 0, which would make any local shower an outlier.
+    pub min_mad_mm: f64,
+}
+
+/// Outcome of the check for one run, included in the run summary.
+#[derive(Debug, Default, Serialize)]
+pub struct OutlierReport {
+    pub station_days_checked: usize,
+    /// Station-days without a value or with too few neighbours to compare against.
+    pub station_days_skipped: usize,
+    pub outliers: usize,
+    /// The first few outliers as `station/date=value (z=...)`.
+    pub examples: Vec<String>,
+}
+
+/// Score every row against the other stations in its electricity area on the same
+/// day, setting `spatial_z` and `spatial_outlier`.
+///
+/// Each station is compared with the median and MAD of the *other* stations, so a
+/// single spike can't pull the reference towards itself. Rows that can't be scored
+/// keep both fields `None`.
+pub fn detect(rows: &mut [PrecipitationRow], settings: &OutlierSettings) -> OutlierReport {
+    let mut groups: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        // Stations missing from the station list all have area "??"; they aren't neighbours
+        if row.precipitation_mm.is_some() && row.el_area != "??" {
+            groups
+                .entry((row.el_area.as_str(), row.reference_time.as_str()))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut scores: Vec<(usize, f64)> = Vec::new();
+    for members in groups.values() {
+        if members.len() <= settings.min_neighbours {
+            continue;
+        }
+        let values: Vec<f64> = members
+            .iter()
+            .filter_map(|&i| rows[i].precipitation_mm)
+            .collect();
+        for (pos, &i) in members.iter().enumerate() {
+            let others: Vec<f64> = values
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != pos)
+                .map(|(_, &v)| v)
+                .collect();
+            let centre = median(others.clone());
+            let deviations: Vec<f64> = others.iter().map(|v| (v - centre).abs()).collect();
+            let mad = median(deviations).max(settings.min_mad_mm);
+            scores.push((i, MAD_SCALE * (values[pos] - centre) / mad));
+        }
+    }
+
+    let mut report = OutlierReport {
+        station_days_checked: scores.len(),
+        station_days_skipped: rows.len() - scores.len(),
+        ..OutlierReport::default()
+    };
+    scores.sort_by_key(|&(i, _)| i);
+    for (i, z) in scores {
+        let row = &mut rows[i];
+        let outlier = z.abs() > settings.threshold;
+        row.spatial_z = Some((z * 100.0).round() / 100.0);
+        row.spatial_outlier = Some(outlier);
+        if outlier {
+            report.outliers += 1;
+            if report.examples.len() < MAX_EXAMPLES {
+                report.examples.push(format!(
+                    "{}/{}={} (z={z:.1})",
+                    row.station_id,
+                    row.reference_time,
+                    row.precipitation_mm.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    if report.outliers > 0 {
+        warn!(
+            outliers = report.outliers,
+            examples = %report.examples.join(", "),
+            "Spatial outliers flagged"
+        );
+    }
+    info!(
+        checked = report.station_days_checked,
+        skipped = report.station_days_skipped,
+        outliers = report.outliers,
+        "Checked spatial consistency"
+    );
+    report
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
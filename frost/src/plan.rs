@@ -0,0 +1,233 @@
+use crate::config::{Station, parse_date, yearly_chunks};
+use crate::databricks_client::{BATCH_SIZE, DatabricksClient};
+use crate::error::Result;
+use crate::frost_client::AVAILABLE_SOURCES_PER_REQUEST;
+use crate::gap_fill::{GapFillSettings, IMPUTATION_METHOD};
+use crate::migrations::{MIGRATIONS, added_column};
+use crate::outliers::OutlierSettings;
+use crate::validation::{Rule, ValidationRules};
+use std::path::Path;
+
+/// What an `ingest` run would do, printed by `--dry-run` instead of running it.
+///
+/// Built from the same inputs as a real run (stations, `yearly_chunks`, the SQL
+/// `DatabricksClient` generates) but makes no network calls and writes nothing.
+pub struct IngestPlan<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub stations: &'a [&'static Station],
+    pub parallel: bool,
+    pub preflight: bool,
+    pub bulk_load_threshold: usize,
+    pub db_concurrency: usize,
+    pub stop_warehouse_after: bool,
+    pub verify: bool,
+    pub validation: &'a ValidationRules,
+    pub outliers: &'a OutlierSettings,
+    /// Set with `--fill-gaps`.
+    pub gap_fill: Option<&'a GapFillSettings>,
+    pub target: PlanTarget<'a>,
+}
+
+pub enum PlanTarget<'a> {
+    /// Raw rows, and gap-filled rows if `--fill-gaps` is set.
+    Csv(&'a Path, &'a Path),
+    Databricks(&'a DatabricksClient),
+}
+
+impl IngestPlan<'_> {
+    pub fn print(&self) -> Result<()> {
+        let chunks = yearly_chunks(self.from, self.to)?;
+        let days = (parse_date(self.to)? - parse_date(self.from)?)
+            .num_days()
+            .max(0) as usize;
+
+        println!("Dry run: nothing will be fetched, deleted or written.");
+        println!();
+        println!(
+            "Period: {} .. {} (exclusive), {days} days",
+            self.from, self.to
+        );
+
+        println!();
+        println!("Stations ({}):", self.stations.len());
+        for s in self.stations {
+            println!("  {} {:<9} {}", s.el_area, s.id, s.name);
+        }
+
+        println!();
+        println!("Chunks ({}):", chunks.len());
+        for (from, to) in &chunks {
+            println!("  {from} .. {to}");
+        }
+
+        let preflight_requests = if self.preflight {
+            self.stations.len().div_ceil(AVAILABLE_SOURCES_PER_REQUEST)
+        } else {
+            0
+        };
+        let (observation_requests, how) = if self.parallel {
+            (
+                self.stations.len() * chunks.len(),
+                format!(
+                    "{} stations x {} chunks, parallel",
+                    self.stations.len(),
+                    chunks.len()
+                ),
+            )
+        } else {
+            (
+                1,
+                "all stations and the whole period in one request".to_string(),
+            )
+        };
+
+        println!();
+        println!(
+            "Frost requests (estimated): {}",
+            preflight_requests + observation_requests
+        );
+        if self.preflight {
+            println!(
+                "  availableTimeSeries: {preflight_requests} (pre-flight; may skip observation requests)"
+            );
+        }
+        println!("  observations:        {observation_requests} ({how})");
+
+        // Daily series: at most one row per station per day
+        let max_rows = self.stations.len() * days;
+        println!();
+        println!("Rows (upper bound): {max_rows}");
+
+        println!();
+        println!(
+            "Dedup: one row per station, date, element and time offset (best quality code wins)"
+        );
+
+        println!();
+        println!("Validation (violations are reported in the run summary):");
+        for rule in Rule::ALL {
+            let action = self.validation.action(rule).as_str();
+            match rule {
+                Rule::ImplausibleTotal => println!(
+                    "  {:<18} {action} (> {} mm)",
+                    rule.name(),
+                    self.validation.max_daily_mm
+                ),
+                _ => println!("  {:<18} {action}", rule.name()),
+            }
+        }
+
+        println!();
+        println!(
+            "Spatial outliers: robust z-score against other stations in the area each day, \
+             flagged above {} (needs {} neighbours, MAD at least {} mm)",
+            self.outliers.threshold, self.outliers.min_neighbours, self.outliers.min_mad_mm
+        );
+
+        if let Some(gap_fill) = self.gap_fill {
+            println!();
+            println!(
+                "Gap fill: missing station-days estimated by {IMPUTATION_METHOD} from other \
+                 stations in the area (needs {} donors, {} overlapping days); written separately",
+                gap_fill.min_donors, gap_fill.min_overlap_days
+            );
+        }
+
+        println!();
+        match &self.target {
+            PlanTarget::Csv(path, gap_filled_path) => {
+                println!("Output: CSV file {}", path.display());
+                if self.gap_fill.is_some() {
+                    println!("Gap-filled output: CSV file {}", gap_filled_path.display());
+                }
+            }
+            PlanTarget::Databricks(db) => {
+                let batches = max_rows.div_ceil(BATCH_SIZE);
+                println!("Output: Databricks table {}", db.table_name());
+                println!(
+                    "Warehouse: {} (started if stopped, waiting up to {}s; statements cancelled after {}s{})",
+                    db.warehouse_id(),
+                    db.warehouse_timeout().as_secs(),
+                    db.statement_timeout().as_secs(),
+                    if self.stop_warehouse_after {
+                        "; stopped after the run"
+                    } else {
+                        ""
+                    }
+                );
+                println!(
+                    "Delete range: reference_time >= '{}' AND reference_time < '{}'",
+                    self.from, self.to
+                );
+                println!();
+                println!(
+                    "Migrations ({}; only those not yet applied will run):",
+                    MIGRATIONS.len()
+                );
+                println!();
+                println!("{};", db.create_schema_sql());
+                println!();
+                for migration in MIGRATIONS {
+                    println!("-- {}: {}", migration.version, migration.description);
+                    for sql in migration.render(db) {
+                        if let Some((table, column)) = added_column(&sql) {
+                            println!("-- (skipped if {table}.{column} already exists)");
+                        }
+                        println!("{sql};");
+                    }
+                    println!();
+                }
+                println!("{};", db.delete_existing_sql(self.from, self.to));
+                println!();
+                if max_rows > self.bulk_load_threshold {
+                    // Decided on the actual row count, which is only known after the fetch
+                    println!(
+                        "Load: COPY INTO from a CSV in {} if more than {} rows are fetched, \
+                         else INSERT batches of up to {BATCH_SIZE} rows or {} bytes \
+                         (at least {batches}), {} at a time:",
+                        db.volume_name(),
+                        self.bulk_load_threshold,
+                        db.max_statement_bytes(),
+                        self.db_concurrency
+                    );
+                    println!();
+                    println!(
+                        "{};",
+                        db.copy_into_sql(&db.volume_path("<run_id>.csv"), "<run_id>")
+                    );
+                } else {
+                    println!(
+                        "Load: INSERT batches of up to {BATCH_SIZE} rows or {} bytes \
+                         (at least {batches}), {} at a time:",
+                        db.max_statement_bytes(),
+                        self.db_concurrency
+                    );
+                    println!();
+                    println!("{};", db.insert_sql("(...), (...), ..."));
+                }
+                println!();
+                println!(
+                    "Verify: stored rows per station compared with rows loaded ({})",
+                    if self.verify {
+                        "a mismatch fails the run"
+                    } else {
+                        "reported in the run summary"
+                    }
+                );
+                println!();
+                if self.gap_fill.is_some() {
+                    println!(
+                        "Gap-filled output: {} (date range replaced, same batching):",
+                        db.gap_filled_table_name()
+                    );
+                    println!();
+                    println!("{};", db.delete_gap_filled_sql(self.from, self.to));
+                    println!("{};", db.insert_gap_filled_sql("(...), (...), ..."));
+                    println!();
+                }
+            }
+        }
+        Ok(())
+    }
+}
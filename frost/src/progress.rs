@@ -0,0 +1,130 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// How often to log progress when stderr is not a terminal.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The bar currently drawn on stderr, so log lines can be printed above it.
+static ACTIVE_BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Progress of a parallel fetch: tasks done, rows fetched, throughput and ETA.
+///
+/// Draws a progress bar when stderr is a terminal, otherwise logs a summary line
+/// every [`REPORT_INTERVAL`] so scheduled runs still show where they are.
+pub struct Progress {
+    total: usize,
+    done: AtomicUsize,
+    failed: AtomicUsize,
+    rows: AtomicUsize,
+    started: Instant,
+    display: Display,
+}
+
+enum Display {
+    Bar(ProgressBar),
+    Log { last_report: Mutex<Instant> },
+}
+
+impl Progress {
+    pub fn new(total: usize) -> Self {
+        let display = if io::stderr().is_terminal() {
+            let bar = ProgressBar::new(total as u64).with_style(
+                ProgressStyle::with_template(
+                    "{spinner} [{elapsed_precise}] {wide_bar} {pos}/{len} tasks  {msg}  ETA {eta}",
+                )
+                .expect("valid progress template"),
+            );
+            bar.enable_steady_tick(Duration::from_millis(200));
+            *ACTIVE_BAR.lock().unwrap() = Some(bar.clone());
+            Display::Bar(bar)
+        } else {
+            Display::Log {
+                last_report: Mutex::new(Instant::now()),
+            }
+        };
+
+        Self {
+            total,
+            done: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            rows: AtomicUsize::new(0),
+            started: Instant::now(),
+            display,
+        }
+    }
+
+    /// Record one finished task (a station/year request) and the rows it returned.
+    pub fn task_done(&self, rows: usize, failed: bool) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        let rows = self.rows.fetch_add(rows, Ordering::Relaxed) + rows;
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        match &self.display {
+            Display::Bar(bar) => {
+                bar.set_position(done as u64);
+                bar.set_message(format!(
+                    "{rows} rows, {:.0} rows/s",
+                    self.rows_per_sec(rows)
+                ));
+            }
+            Display::Log { last_report } => {
+                let mut last = last_report.lock().unwrap();
+                if last.elapsed() >= REPORT_INTERVAL {
+                    *last = Instant::now();
+                    self.report(done, rows);
+                }
+            }
+        }
+    }
+
+    fn report(&self, done: usize, rows: usize) {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let eta_secs = (elapsed / done as f64 * (self.total - done) as f64).round();
+        info!(
+            done,
+            total = self.total,
+            failed = self.failed.load(Ordering::Relaxed),
+            rows,
+            rows_per_sec = format_args!("{:.0}", self.rows_per_sec(rows)),
+            eta_secs,
+            "Fetch progress"
+        );
+    }
+
+    fn rows_per_sec(&self, rows: usize) -> f64 {
+        rows as f64 / self.started.elapsed().as_secs_f64().max(0.001)
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Display::Bar(bar) = &self.display {
+            ACTIVE_BAR.lock().unwrap().take();
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Stderr writer for the log layer that clears the progress bar while a line is
+/// written and redraws it afterwards, so logs and bar don't overwrite each other.
+pub struct StderrWriter;
+
+impl Write for StderrWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bar = ACTIVE_BAR.lock().unwrap().clone();
+        match bar {
+            Some(bar) => bar.suspend(|| io::stderr().write(buf)),
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
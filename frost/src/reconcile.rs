@@ -0,0 +1,137 @@
+use crate::databricks_client::{DatabricksClient, sql_literal};
+use crate::error::Result;
+use crate::frost_client::PrecipitationRow;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Stored versus loaded row counts for one run, per station.
+#[derive(Debug, Serialize)]
+pub struct Reconciliation {
+    pub stations_checked: usize,
+    pub rows_expected: usize,
+    /// Rows tagged with the run's ID.
+    pub rows_stored: usize,
+    /// Loaded rows inside the requested range.
+    pub rows_expected_in_range: usize,
+    /// Rows inside the requested range, whichever run wrote them.
+    pub rows_stored_in_range: usize,
+    /// Stations where either stored count differs from what was loaded.
+    pub mismatches: Vec<StationMismatch>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StationMismatch {
+    pub station_id: String,
+    pub expected: usize,
+    pub stored: usize,
+    pub expected_in_range: usize,
+    pub stored_in_range: usize,
+}
+
+impl Reconciliation {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    expected: usize,
+    stored: usize,
+    expected_in_range: usize,
+    stored_in_range: usize,
+}
+
+/// Compare `rows`, the rows handed to the load, with the target table, per station.
+///
+/// Two counts are checked: the rows tagged with `run_id`, wherever they landed,
+/// and the rows inside `from..to` written by any run. The first catches rows the
+/// load lost; the second catches rows the delete should have replaced but didn't,
+/// such as a concurrent run's or a failed delete's leftovers.
+pub fn reconcile(
+    db: &DatabricksClient,
+    run_id: &str,
+    from: &str,
+    to: &str,
+    rows: &[PrecipitationRow],
+) -> Result<Reconciliation> {
+    #[derive(Deserialize)]
+    struct StationCount {
+        station_id: String,
+        stored: usize,
+        stored_in_range: usize,
+    }
+
+    let mut counts: BTreeMap<String, Counts> = BTreeMap::new();
+    for row in rows {
+        let c = counts.entry(row.station_id.clone()).or_default();
+        c.expected += 1;
+        // Dates are ISO formatted, so string order is date order
+        if row.reference_time.as_str() >= from && row.reference_time.as_str() < to {
+            c.expected_in_range += 1;
+        }
+    }
+
+    let in_range = format!(
+        "reference_time >= {from} AND reference_time < {to}",
+        from = sql_literal(from),
+        to = sql_literal(to),
+    );
+    let stored: Vec<StationCount> = db.query(&format!(
+        "SELECT station_id, \
+         COUNT_IF(ingest_run_id = {run_id}) AS stored, \
+         COUNT_IF({in_range}) AS stored_in_range \
+         FROM {table} \
+         WHERE ingest_run_id = {run_id} OR ({in_range}) \
+         GROUP BY station_id",
+        table = db.table_name(),
+        run_id = sql_literal(run_id),
+    ))?;
+    for s in stored {
+        let c = counts.entry(s.station_id).or_default();
+        c.stored = s.stored;
+        c.stored_in_range = s.stored_in_range;
+    }
+
+    let mismatches: Vec<StationMismatch> = counts
+        .iter()
+        .filter(|(_, c)| c.expected != c.stored || c.expected_in_range != c.stored_in_range)
+        .map(|(station, c)| StationMismatch {
+            station_id: station.clone(),
+            expected: c.expected,
+            stored: c.stored,
+            expected_in_range: c.expected_in_range,
+            stored_in_range: c.stored_in_range,
+        })
+        .collect();
+
+    let reconciliation = Reconciliation {
+        stations_checked: counts.len(),
+        rows_expected: counts.values().map(|c| c.expected).sum(),
+        rows_stored: counts.values().map(|c| c.stored).sum(),
+        rows_expected_in_range: counts.values().map(|c| c.expected_in_range).sum(),
+        rows_stored_in_range: counts.values().map(|c| c.stored_in_range).sum(),
+        mismatches,
+    };
+
+    for m in &reconciliation.mismatches {
+        warn!(
+            station = %m.station_id,
+            expected = m.expected,
+            stored = m.stored,
+            expected_in_range = m.expected_in_range,
+            stored_in_range = m.stored_in_range,
+            "Stored row count differs from rows loaded"
+        );
+    }
+    info!(
+        stations = reconciliation.stations_checked,
+        rows_expected = reconciliation.rows_expected,
+        rows_stored = reconciliation.rows_stored,
+        rows_stored_in_range = reconciliation.rows_stored_in_range,
+        mismatches = reconciliation.mismatches.len(),
+        "Reconciled stored rows"
+    );
+    Ok(reconciliation)
+}
@@ -0,0 +1,223 @@
+use crate::databricks_client::{DatabricksClient, sql_literal};
+use crate::error::{FrostCliError, Result};
+use crate::summary::RunSummary;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// Version of this CLI, stamped on every row and run record.
+pub const CLI_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Audit table in the raw schema with one row per `ingest` run (see migration 3).
+pub const RUNS_TABLE: &str = "ingest_runs";
+
+/// Write the finished run to the `ingest_runs` audit table.
+pub fn record(db: &DatabricksClient, summary: &RunSummary) -> Result<()> {
+    let parameters = serde_json::to_string(&summary.parameters)
+        .map_err(|e| FrostCliError::config(format!("Could not serialize run parameters: {e}")))?;
+    let rows_fetched: usize = summary.rows_fetched.values().sum();
+    let rows_written = summary.rows_written.get("databricks").copied().unwrap_or(0);
+    let optional = |s: Option<&str>| s.map_or("NULL".to_string(), sql_literal);
+
+    let sql = format!(
+        "INSERT INTO {schema}.{RUNS_TABLE} \
+         (run_id, table_name, cli_version, status, started_at, finished_at, duration_secs, \
+          from_date, to_date, parameters, stations, rows_fetched, rows_written, \
+          frost_requests, databricks_statements, table_version_before, \
+          gap_filled_version_before, error) \
+         VALUES ({run_id}, {table}, {version}, {status}, CAST({started_at} AS TIMESTAMP), \
+          CAST({finished_at} AS TIMESTAMP), {duration}, CAST({from} AS DATE), CAST({to} AS DATE), \
+          {parameters}, {stations}, {rows_fetched}, {rows_written}, {frost_requests}, \
+          {databricks_statements}, {table_version_before}, {gap_filled_version_before}, {error})",
+        schema = db.schema_name(),
+        run_id = sql_literal(&summary.run_id),
+        table = sql_literal(&db.table_name()),
+        version = sql_literal(CLI_VERSION),
+        status = sql_literal(summary.status.as_str()),
+        started_at = sql_literal(&summary.started_at),
+        finished_at = optional(summary.finished_at.as_deref()),
+        duration = summary.duration_secs,
+        from = sql_literal(&summary.parameters.from),
+        to = sql_literal(&summary.parameters.to),
+        parameters = sql_literal(&parameters),
+        stations = sql_literal(&summary.stations.join(",")),
+        frost_requests = summary.requests.frost,
+        databricks_statements = summary.requests.databricks_statements,
+        table_version_before = summary
+            .table_version_before
+            .map_or("NULL".to_string(), |v| v.to_string()),
+        gap_filled_version_before = summary
+            .gap_filled_version_before
+            .map_or("NULL".to_string(), |v| v.to_string()),
+        error = optional(summary.error.as_deref()),
+    );
+
+    db.execute(&sql)?;
+    info!(run_id = %summary.run_id, status = summary.status.as_str(), "Recorded run in {RUNS_TABLE}");
+    Ok(())
+}
+
+/// What `rollback` needs to know about an earlier run.
+#[derive(Debug, Deserialize)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub table_name: String,
+    pub status: String,
+    pub started_at: String,
+    #[serde(rename = "from_date")]
+    pub from: String,
+    #[serde(rename = "to_date")]
+    pub to: String,
+    pub table_version_before: Option<i64>,
+    /// Set if the run replaced its range in the gap-filled table.
+    pub gap_filled_version_before: Option<i64>,
+}
+
+/// Look up a run in the audit table.
+pub fn find(db: &DatabricksClient, run_id: &str) -> Result<Option<RunRecord>> {
+    let rows: Vec<RunRecord> = db.query(&format!(
+        "SELECT run_id, table_name, status, CAST(started_at AS STRING) AS started_at, \
+         CAST(from_date AS STRING) AS from_date, CAST(to_date AS STRING) AS to_date, \
+         table_version_before, gap_filled_version_before \
+         FROM {schema}.{RUNS_TABLE} WHERE run_id = {run_id}",
+        schema = db.schema_name(),
+        run_id = sql_literal(run_id),
+    ))?;
+    Ok(rows.into_iter().next())
+}
+
+/// Runs started after `run` that deleted and rewrote an overlapping date range.
+/// Restoring `run`'s range would clobber their rows.
+fn later_overlapping(db: &DatabricksClient, run: &RunRecord) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct LaterRun {
+        run_id: String,
+    }
+
+    let rows: Vec<LaterRun> = db.query(&format!(
+        "SELECT run_id FROM {schema}.{RUNS_TABLE} \
+         WHERE table_name = {table} AND started_at > CAST({started_at} AS TIMESTAMP) \
+         AND from_date < CAST({to} AS DATE) AND to_date > CAST({from} AS DATE) \
+         AND table_version_before IS NOT NULL AND status <> 'rolled_back' \
+         ORDER BY started_at",
+        schema = db.schema_name(),
+        table = sql_literal(&run.table_name),
+        started_at = sql_literal(&run.started_at),
+        from = sql_literal(&run.from),
+        to = sql_literal(&run.to),
+    ))?;
+    Ok(rows.into_iter().map(|row| row.run_id).collect())
+}
+
+/// Statements that undo `run`: drop the rows it inserted, then, if it deleted its
+/// date range first, copy that range back from the table version before the run.
+/// The gap-filled table is restored the same way if the run wrote to it.
+///
+/// When restoring, the whole range is cleared first, so the result is the same
+/// whether or not the run got as far as its DELETE, and re-running a rollback that
+/// failed part-way doesn't restore the rows twice.
+pub fn rollback_statements(db: &DatabricksClient, run: &RunRecord) -> Vec<String> {
+    let table = db.table_name();
+    let run_id = sql_literal(&run.run_id);
+    let (from, to) = (sql_literal(&run.from), sql_literal(&run.to));
+    let mut statements = Vec::new();
+    match run.table_version_before {
+        Some(version) => {
+            statements.push(format!(
+                "DELETE FROM {table} WHERE ingest_run_id = {run_id} \
+                 OR (reference_time >= {from} AND reference_time < {to})"
+            ));
+            // BY NAME: the old version may predate columns added by later migrations
+            statements.push(format!(
+                "INSERT INTO {table} BY NAME \
+                 SELECT * FROM {table} VERSION AS OF {version} \
+                 WHERE reference_time >= {from} AND reference_time < {to}"
+            ));
+        }
+        // Recorded before the DELETE, so without it the run never touched the range
+        None => statements.push(format!(
+            "DELETE FROM {table} WHERE ingest_run_id = {run_id}"
+        )),
+    }
+    if let Some(version) = run.gap_filled_version_before {
+        let gap_filled = db.gap_filled_table_name();
+        statements.push(format!(
+            "DELETE FROM {gap_filled} WHERE ingest_run_id = {run_id} \
+             OR (reference_time >= {from} AND reference_time < {to})"
+        ));
+        statements.push(format!(
+            "INSERT INTO {gap_filled} BY NAME \
+             SELECT * FROM {gap_filled} VERSION AS OF {version} \
+             WHERE reference_time >= {from} AND reference_time < {to}"
+        ));
+    }
+    statements.push(format!(
+        "UPDATE {schema}.{RUNS_TABLE} SET status = 'rolled_back' WHERE run_id = {run_id}",
+        schema = db.schema_name(),
+    ));
+    statements
+}
+
+/// Undo an ingest run. Refuses if a later run overwrote an overlapping range,
+/// unless `force` is set; with `dry_run`, only prints the statements.
+pub fn rollback(db: &DatabricksClient, run_id: &str, force: bool, dry_run: bool) -> Result<()> {
+    let run_id = uuid::Uuid::parse_str(run_id)
+        .map_err(|_| FrostCliError::config(format!("Invalid run ID: {run_id}")))?
+        .to_string();
+
+    let run = find(db, &run_id)?.ok_or_else(|| {
+        FrostCliError::config(format!(
+            "No run {run_id} in {}.{RUNS_TABLE}",
+            db.schema_name()
+        ))
+    })?;
+    if run.table_name != db.table_name() {
+        return Err(FrostCliError::config(format!(
+            "Run {run_id} wrote to {}, not {} (select it with --catalog/--schema/--table)",
+            run.table_name,
+            db.table_name()
+        )));
+    }
+    if run.status == "rolled_back" {
+        return Err(FrostCliError::config(format!(
+            "Run {run_id} has already been rolled back"
+        )));
+    }
+
+    let later = later_overlapping(db, &run)?;
+    if !later.is_empty() {
+        if !force {
+            return Err(FrostCliError::config(format!(
+                "Later run(s) {} reloaded part of {}..{}; roll those back first or pass --force",
+                later.join(", "),
+                run.from,
+                run.to
+            )));
+        }
+        warn!(later = %later.join(", "), "Rolling back despite later overlapping runs (--force)");
+    }
+    if run.table_version_before.is_none() {
+        warn!(run_id = %run_id, "Run has no recorded table version; only its own rows will be deleted");
+    }
+
+    let statements = rollback_statements(db, &run);
+    if dry_run {
+        for sql in &statements {
+            println!("{sql};");
+        }
+        return Ok(());
+    }
+
+    info!(
+        run_id = %run_id,
+        from = %run.from,
+        to = %run.to,
+        restore_from_version = ?run.table_version_before,
+        restore_gap_filled_from_version = ?run.gap_filled_version_before,
+        "Rolling back run"
+    );
+    for sql in &statements {
+        db.execute(sql)?;
+    }
+    info!(run_id = %run_id, table = %db.table_name(), "Rollback complete");
+    Ok(())
+}
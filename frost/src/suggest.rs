@@ -0,0 +1,140 @@
+use crate::availability::Availability;
+use crate::catalogue::{CatalogueStation, SelectionMetrics, StationCatalogue};
+use crate::config::{ElArea, parse_date};
+use crate::error::Result;
+use crate::frost_client::{DiscoveredStation, FrostClient};
+use crate::geo::distance_km;
+use tracing::info;
+
+// Score = weighted sum of three terms, each in 0..1.
+const COVERAGE_WEIGHT: f64 = 0.6;
+const ACTIVITY_WEIGHT: f64 = 0.2;
+const SPREAD_WEIGHT: f64 = 0.2;
+
+/// Distance to the nearest already-picked station at which the spread term maxes out.
+const SPREAD_SCALE_KM: f64 = 100.0;
+
+/// A candidate with its station-level metrics (spread is computed during selection).
+struct Candidate {
+    station: DiscoveredStation,
+    coverage: f64,
+    active: bool,
+}
+
+/// Pick `count` stations per area from everything Frost reports for daily precipitation.
+///
+/// Stations are picked greedily: coverage of `from..to` and activity are fixed per
+/// station, while the spread term rewards distance from stations already picked in
+/// the same area, so the set doesn't cluster around one city.
+pub fn suggest_stations(
+    frost: &FrostClient,
+    areas: &[ElArea],
+    count: usize,
+    from: &str,
+    to: &str,
+) -> Result<StationCatalogue> {
+    let from_date = parse_date(from)?;
+    let to_date = parse_date(to)?;
+
+    let candidates: Vec<DiscoveredStation> = frost
+        .list_precipitation_stations()?
+        .into_iter()
+        .filter(|s| s.el_area.is_some_and(|a| areas.contains(&a)))
+        .collect();
+
+    let ids: Vec<&str> = candidates.iter().map(|s| s.id.as_str()).collect();
+    let series = frost.available_time_series(&ids, from, to)?;
+    let availability = Availability::from_series(&series, from_date, to_date);
+
+    let mut scored: Vec<Candidate> = Vec::new();
+    for station in candidates {
+        let coverage = availability.coverage(&station.id, from_date, to_date);
+        if coverage <= 0.0 {
+            continue;
+        }
+        let active = station.is_active && availability.ended_on(&station.id).is_none();
+        scored.push(Candidate {
+            station,
+            coverage,
+            active,
+        });
+    }
+
+    info!(candidates = scored.len(), "Scored candidate stations");
+
+    let mut selected = Vec::new();
+    for &area in areas {
+        let mut pool: Vec<Candidate> = Vec::new();
+        let mut rest = Vec::new();
+        for c in scored {
+            if c.station.el_area == Some(area) {
+                pool.push(c);
+            } else {
+                rest.push(c);
+            }
+        }
+        scored = rest;
+        selected.extend(select_spread(pool, area, count));
+    }
+
+    Ok(StationCatalogue {
+        generated_at: Some(chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+        period: Some(format!("{from}/{to}")),
+        stations: selected,
+    })
+}
+
+/// Greedily pick the best-scoring candidate, recomputing the spread term after each pick.
+fn select_spread(mut pool: Vec<Candidate>, area: ElArea, count: usize) -> Vec<CatalogueStation> {
+    let mut picked: Vec<CatalogueStation> = Vec::new();
+
+    while picked.len() < count && !pool.is_empty() {
+        let (best_idx, best_score, nearest_km) = pool
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let nearest_km = nearest_distance_km(&c.station, &picked);
+                let spread = match (picked.is_empty(), nearest_km) {
+                    (true, _) => 1.0,
+                    (false, Some(km)) => (km / SPREAD_SCALE_KM).min(1.0),
+                    // No coordinates: can't show it adds spread, so it gets none
+                    (false, None) => 0.0,
+                };
+                let score = COVERAGE_WEIGHT * c.coverage
+                    + ACTIVITY_WEIGHT * f64::from(u8::from(c.active))
+                    + SPREAD_WEIGHT * spread;
+                (i, score, nearest_km)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("pool is not empty");
+
+        let c = pool.swap_remove(best_idx);
+        picked.push(CatalogueStation {
+            id: c.station.id,
+            name: c.station.name,
+            el_area: area.to_string(),
+            latitude: c.station.latitude,
+            longitude: c.station.longitude,
+            selection: Some(SelectionMetrics {
+                score: round3(best_score),
+                coverage: round3(c.coverage),
+                active: c.active,
+                nearest_selected_km: nearest_km.map(|km| km.round()),
+            }),
+        });
+    }
+
+    picked
+}
+
+fn nearest_distance_km(station: &DiscoveredStation, picked: &[CatalogueStation]) -> Option<f64> {
+    let (lat, lon) = (station.latitude?, station.longitude?);
+    picked
+        .iter()
+        .filter_map(|p| Some(distance_km(lat, lon, p.latitude?, p.longitude?)))
+        .min_by(f64::total_cmp)
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
@@ -0,0 +1,191 @@
+use crate::error::{FrostCliError, Result};
+use crate::frost_client::PrecipitationRow;
+use crate::gap_fill::GapFillReport;
+use crate::outliers::OutlierReport;
+use crate::reconcile::Reconciliation;
+use crate::runs::CLI_VERSION;
+use crate::validation::ValidationReport;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+use tracing::info;
+
+/// Machine-readable record of one `ingest` run, written with `--summary-json`.
+///
+/// Written on failure too (with `status = "failed"`), so orchestration can
+/// always read the outcome from the file instead of parsing logs.
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    /// Stamped on every row this run writes (`ingest_run_id`) and its `ingest_runs` record.
+    pub run_id: String,
+    pub cli_version: &'static str,
+    pub status: RunStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_secs: f64,
+    pub parameters: RunParameters,
+    pub stations: Vec<String>,
+    pub chunks: ChunkCounts,
+    /// Rows returned by Frost, per station ID.
+    pub rows_fetched: BTreeMap<String, usize>,
+    /// Repeated observations dropped after the fetch (see `dedup`).
+    pub duplicates_removed: usize,
+    /// Rule violations found in the fetched rows, and what was done about them.
+    pub validation: Option<ValidationReport>,
+    /// Station-days that disagree with the other stations in their area (see `outliers`).
+    pub outliers: Option<OutlierReport>,
+    /// Missing station-days filled from neighbours, with `--fill-gaps`.
+    pub gap_fill: Option<GapFillReport>,
+    /// Rows written, per sink ("csv", "databricks").
+    pub rows_written: BTreeMap<String, usize>,
+    pub requests: RequestCounts,
+    /// Delta version of the target table just before this run deleted its date range.
+    pub table_version_before: Option<i64>,
+    /// Delta version of the gap-filled table just before this run replaced its range
+    /// there (with `--fill-gaps`).
+    pub gap_filled_version_before: Option<i64>,
+    /// Stored versus loaded row counts per station, checked after a Databricks load.
+    pub reconciliation: Option<Reconciliation>,
+    pub error: Option<String>,
+    pub exit_code: u8,
+    #[serde(skip)]
+    timer: Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Succeeded,
+    /// Finished without error, but Frost returned no rows.
+    NoData,
+    Failed,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::NoData => "no_data",
+            RunStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunParameters {
+    pub from: String,
+    pub to: String,
+    pub areas: Vec<String>,
+    pub output: String,
+    pub parallel: bool,
+    pub preflight: bool,
+    pub stations_file: Option<String>,
+}
+
+/// Frost requests for observations, one per station/period chunk.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ChunkCounts {
+    /// Requests sent to Frost.
+    pub attempted: usize,
+    /// Requests that returned at least one row.
+    pub succeeded: usize,
+    /// Requests that returned no rows (including 404/412 "no data").
+    pub empty: usize,
+    pub failed: usize,
+    /// Station/periods not requested because the pre-flight check found no series.
+    pub skipped: usize,
+}
+
+impl ChunkCounts {
+    pub fn add(&mut self, other: &ChunkCounts) {
+        self.attempted += other.attempted;
+        self.succeeded += other.succeeded;
+        self.empty += other.empty;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Work done against each service.
+#[derive(Debug, Default, Serialize)]
+pub struct RequestCounts {
+    /// HTTP requests to Frost (all endpoints, including pre-flight).
+    pub frost: usize,
+    /// SQL statements submitted to the warehouse, including DDL. Status polls,
+    /// result chunks, file uploads, warehouse and OAuth token calls aren't counted.
+    pub databricks_statements: usize,
+}
+
+impl RunSummary {
+    pub fn start(parameters: RunParameters) -> Self {
+        Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            cli_version: CLI_VERSION,
+            status: RunStatus::Running,
+            started_at: now_rfc3339(),
+            finished_at: None,
+            duration_secs: 0.0,
+            parameters,
+            stations: Vec::new(),
+            chunks: ChunkCounts::default(),
+            rows_fetched: BTreeMap::new(),
+            duplicates_removed: 0,
+            validation: None,
+            outliers: None,
+            gap_fill: None,
+            rows_written: BTreeMap::new(),
+            requests: RequestCounts::default(),
+            table_version_before: None,
+            gap_filled_version_before: None,
+            reconciliation: None,
+            error: None,
+            exit_code: 0,
+            timer: Instant::now(),
+        }
+    }
+
+    pub fn record_fetched(&mut self, rows: &[PrecipitationRow]) {
+        for row in rows {
+            *self.rows_fetched.entry(row.station_id.clone()).or_default() += 1;
+        }
+    }
+
+    pub fn record_written(&mut self, sink: &str, rows: usize) {
+        self.rows_written.insert(sink.to_string(), rows);
+    }
+
+    pub fn finish(&mut self, result: &Result<()>) {
+        self.finished_at = Some(now_rfc3339());
+        self.duration_secs = (self.timer.elapsed().as_secs_f64() * 1000.0).round() / 1000.0;
+        match result {
+            Ok(()) if self.rows_fetched.is_empty() => self.status = RunStatus::NoData,
+            Ok(()) => self.status = RunStatus::Succeeded,
+            Err(e) => {
+                self.status = RunStatus::Failed;
+                self.error = Some(e.to_string());
+                self.exit_code = e.exit_code();
+            }
+        }
+    }
+
+    /// Write the summary as JSON to `path`, or to stdout when `path` is `-`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| FrostCliError::config(format!("Could not serialize run summary: {e}")))?;
+
+        if path == Path::new("-") {
+            println!("{json}");
+        } else {
+            std::fs::write(path, json + "\n")?;
+            info!(path = %path.display(), "Wrote run summary");
+        }
+        Ok(())
+    }
+}
+
+fn now_rfc3339() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
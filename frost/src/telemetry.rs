@@ -0,0 +1,318 @@
+use crate::error::{FrostCliError, Result};
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{KeyValue, Value, global};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::{SdkMeterProvider, Temporality};
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use serde_json::{Map, json};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+const SERVICE_NAME: &str = "frost";
+
+/// Where to send OpenTelemetry traces and metrics. Both may be set at once.
+#[derive(Debug, Default, Clone)]
+pub struct TelemetryOptions {
+    /// OTLP/HTTP base URL, e.g. `http://localhost:4318` (`/v1/traces`, `/v1/metrics` are appended).
+    pub otlp_endpoint: Option<String>,
+    /// JSON-lines file with one record per finished span and per metric data point.
+    pub file: Option<std::path::PathBuf>,
+}
+
+/// Flushes and shuts down the exporters when dropped (end of `main`).
+pub struct TelemetryGuard {
+    tracer_provider: Option<SdkTracerProvider>,
+    meter_provider: Option<SdkMeterProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        // Errors here can't be reported through tracing anymore
+        if let Some(p) = self.tracer_provider.take()
+            && let Err(e) = p.shutdown()
+        {
+            eprintln!("OpenTelemetry trace shutdown failed: {e}");
+        }
+        if let Some(p) = self.meter_provider.take()
+            && let Err(e) = p.shutdown()
+        {
+            eprintln!("OpenTelemetry metrics shutdown failed: {e}");
+        }
+    }
+}
+
+/// Set up exporters for the configured destinations.
+///
+/// Returns the guard plus a `tracing` layer that turns spans into OTel spans
+/// (`None` when telemetry is off, which `tracing_subscriber` treats as a no-op layer).
+pub fn init<S>(options: &TelemetryOptions) -> Result<(TelemetryGuard, Option<impl Layer<S>>)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    if options.otlp_endpoint.is_none() && options.file.is_none() {
+        let guard = TelemetryGuard {
+            tracer_provider: None,
+            meter_provider: None,
+        };
+        return Ok((guard, None));
+    }
+
+    let resource = Resource::builder()
+        .with_service_name(SERVICE_NAME)
+        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let mut tracer_builder = SdkTracerProvider::builder().with_resource(resource.clone());
+    let mut meter_builder = SdkMeterProvider::builder().with_resource(resource);
+
+    if let Some(endpoint) = &options.otlp_endpoint {
+        let base = endpoint.trim_end_matches('/');
+        let span_exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{base}/v1/traces"))
+            .build()
+            .map_err(|e| FrostCliError::config(format!("OTLP trace exporter: {e}")))?;
+        let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{base}/v1/metrics"))
+            .build()
+            .map_err(|e| FrostCliError::config(format!("OTLP metric exporter: {e}")))?;
+        tracer_builder = tracer_builder.with_batch_exporter(span_exporter);
+        meter_builder = meter_builder.with_periodic_exporter(metric_exporter);
+    }
+
+    if let Some(path) = &options.file {
+        let file = JsonLinesFile::create(path)?;
+        tracer_builder = tracer_builder.with_batch_exporter(file.clone());
+        meter_builder = meter_builder.with_periodic_exporter(file);
+    }
+
+    let tracer_provider = tracer_builder.build();
+    let meter_provider = meter_builder.build();
+    global::set_meter_provider(meter_provider.clone());
+
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer(SERVICE_NAME));
+    let guard = TelemetryGuard {
+        tracer_provider: Some(tracer_provider),
+        meter_provider: Some(meter_provider),
+    };
+    Ok((guard, Some(layer)))
+}
+
+// --- Metrics ---
+
+struct Instruments {
+    frost_requests: Counter<u64>,
+    frost_request_duration: Histogram<f64>,
+    frost_rows: Counter<u64>,
+    databricks_statements: Counter<u64>,
+    databricks_statement_duration: Histogram<f64>,
+    databricks_rows_inserted: Counter<u64>,
+}
+
+/// Instruments are created on first use, after `init` has installed the meter provider.
+/// Without a provider they are no-ops.
+fn instruments() -> &'static Instruments {
+    static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+    INSTRUMENTS.get_or_init(|| {
+        let meter = global::meter_provider().meter(SERVICE_NAME);
+        Instruments {
+            frost_requests: meter
+                .u64_counter("frost.requests")
+                .with_description("HTTP requests sent to the Frost API")
+                .build(),
+            frost_request_duration: meter
+                .f64_histogram("frost.request.duration")
+                .with_unit("s")
+                .with_description("Frost API request latency")
+                .build(),
+            frost_rows: meter
+                .u64_counter("frost.rows")
+                .with_description("Precipitation rows received from Frost")
+                .build(),
+            databricks_statements: meter
+                .u64_counter("databricks.statements")
+                .with_description("SQL statements submitted to Databricks")
+                .build(),
+            databricks_statement_duration: meter
+                .f64_histogram("databricks.statement.duration")
+                .with_unit("s")
+                .with_description("Databricks statement latency")
+                .build(),
+            databricks_rows_inserted: meter
+                .u64_counter("databricks.rows_inserted")
+                .with_description("Rows inserted into Databricks")
+                .build(),
+        }
+    })
+}
+
+/// `outcome` is `"ok"` or an error kind from [`FrostCliError::kind`].
+pub fn record_frost_request(endpoint: &'static str, outcome: &'static str, elapsed: Duration) {
+    let m = instruments();
+    let attrs = [
+        KeyValue::new("endpoint", endpoint),
+        KeyValue::new("outcome", outcome),
+    ];
+    m.frost_requests.add(1, &attrs);
+    m.frost_request_duration
+        .record(elapsed.as_secs_f64(), &attrs[..1]);
+}
+
+pub fn record_frost_rows(rows: usize) {
+    instruments().frost_rows.add(rows as u64, &[]);
+}
+
+/// `kind` is the statement's leading keyword (CREATE, DELETE, INSERT, ...).
+pub fn record_databricks_statement(kind: &str, ok: bool, elapsed: Duration) {
+    let m = instruments();
+    let attrs = [
+        KeyValue::new("kind", kind.to_string()),
+        KeyValue::new("outcome", if ok { "ok" } else { "error" }),
+    ];
+    m.databricks_statements.add(1, &attrs);
+    m.databricks_statement_duration
+        .record(elapsed.as_secs_f64(), &attrs[..1]);
+}
+
+pub fn record_databricks_rows_inserted(rows: usize) {
+    instruments().databricks_rows_inserted.add(rows as u64, &[]);
+}
+
+// --- JSON-lines file exporter ---
+
+/// Writes spans and metric data points as one JSON object per line, with the same
+/// `record_type` / `span_name` / `*_unix_nano` / `attributes` shape as the dbt
+/// telemetry parquet, so both can be queried side by side with DuckDB.
+#[derive(Debug, Clone)]
+struct JsonLinesFile {
+    writer: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl JsonLinesFile {
+    fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self {
+            writer: Arc::new(Mutex::new(BufWriter::new(file))),
+        })
+    }
+
+    fn write_records(&self, records: impl IntoIterator<Item = serde_json::Value>) -> OTelSdkResult {
+        let mut w = self.writer.lock().unwrap();
+        for record in records {
+            writeln!(w, "{record}").map_err(|e| internal_error(&e))?;
+        }
+        w.flush().map_err(|e| internal_error(&e))
+    }
+}
+
+impl SpanExporter for JsonLinesFile {
+    fn export(&self, batch: Vec<SpanData>) -> impl Future<Output = OTelSdkResult> + Send {
+        let records = batch.iter().map(|span| {
+            json!({
+                "record_type": "SpanEnd",
+                "trace_id": span.span_context.trace_id().to_string(),
+                "span_id": span.span_context.span_id().to_string(),
+                "parent_span_id": span.parent_span_id.to_string(),
+                "span_name": span.name,
+                "start_time_unix_nano": unix_nanos(span.start_time),
+                "end_time_unix_nano": unix_nanos(span.end_time),
+                "status": format!("{:?}", span.status),
+                "attributes": attributes_json(&span.attributes),
+            })
+        });
+        std::future::ready(self.write_records(records))
+    }
+}
+
+impl PushMetricExporter for JsonLinesFile {
+    fn export(&self, metrics: &ResourceMetrics) -> impl Future<Output = OTelSdkResult> + Send {
+        let mut records = Vec::new();
+        for metric in metrics.scope_metrics().flat_map(|s| s.metrics()) {
+            let base = |attributes: Map<String, serde_json::Value>| {
+                json!({
+                    "record_type": "Metric",
+                    "metric_name": metric.name(),
+                    "unit": metric.unit(),
+                    "time_unix_nano": unix_nanos(SystemTime::now()),
+                    "attributes": attributes,
+                })
+            };
+            match metric.data() {
+                AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+                    for dp in sum.data_points() {
+                        let mut r = base(attributes_json(dp.attributes()));
+                        r["value"] = json!(dp.value());
+                        records.push(r);
+                    }
+                }
+                AggregatedMetrics::F64(MetricData::Histogram(hist)) => {
+                    for dp in hist.data_points() {
+                        let mut r = base(attributes_json(dp.attributes()));
+                        r["count"] = json!(dp.count());
+                        r["sum"] = json!(dp.sum());
+                        r["min"] = json!(dp.min());
+                        r["max"] = json!(dp.max());
+                        records.push(r);
+                    }
+                }
+                // We only create u64 counters and f64 histograms
+                _ => {}
+            }
+        }
+        std::future::ready(self.write_records(records))
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.writer
+            .lock()
+            .unwrap()
+            .flush()
+            .map_err(|e| internal_error(&e))
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        self.force_flush()
+    }
+
+    fn temporality(&self) -> Temporality {
+        Temporality::Cumulative
+    }
+}
+
+fn internal_error(e: &std::io::Error) -> opentelemetry_sdk::error::OTelSdkError {
+    opentelemetry_sdk::error::OTelSdkError::InternalFailure(e.to_string())
+}
+
+fn unix_nanos(t: SystemTime) -> u128 {
+    t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos())
+}
+
+fn attributes_json<'a>(
+    attrs: impl IntoIterator<Item = &'a KeyValue>,
+) -> Map<String, serde_json::Value> {
+    attrs
+        .into_iter()
+        .map(|kv| {
+            let value = match &kv.value {
+                Value::Bool(b) => json!(b),
+                Value::I64(i) => json!(i),
+                Value::F64(f) => json!(f),
+                Value::String(s) => json!(s.as_str()),
+                other => json!(other.to_string()),
+            };
+            (kv.key.to_string(), value)
+        })
+        .collect()
+}
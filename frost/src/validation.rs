@@ -0,0 +1,221 @@
+use crate::frost_client::PrecipitationRow;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, warn};
+
+/// Offending rows listed per rule in the report; the rest are only counted.
+const MAX_EXAMPLES: usize = 5;
+
+/// What to do with a row that breaks a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Drop the row before it is written.
+    Reject,
+    /// Write the row with the rule's name in `validation_flags`.
+    Flag,
+    /// Fail the run before anything is written.
+    Fail,
+}
+
+impl RuleAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RuleAction::Reject => "reject",
+            RuleAction::Flag => "flag",
+            RuleAction::Fail => "fail",
+        }
+    }
+}
+
+/// A check run on every fetched row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Precipitation below zero.
+    Negative,
+    /// Daily total above `max_daily_mm`.
+    ImplausibleTotal,
+    /// Station and date already seen in this run. Exact repeats are removed by
+    /// `dedup` before validation, so this catches the same day under another
+    /// element or time offset.
+    Duplicate,
+    /// Date outside the requested `--from`..`--to` range. Only `reject` or `fail`
+    /// (see `Config::validation`).
+    OutOfRange,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 4] = [
+        Rule::Negative,
+        Rule::ImplausibleTotal,
+        Rule::Duplicate,
+        Rule::OutOfRange,
+    ];
+
+    /// Name used in config, `validation_flags` and the run summary.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::Negative => "negative",
+            Rule::ImplausibleTotal => "implausible_total",
+            Rule::Duplicate => "duplicate",
+            Rule::OutOfRange => "out_of_range",
+        }
+    }
+}
+
+/// Action per rule, plus the threshold for [`Rule::ImplausibleTotal`].
+#[derive(Debug, Clone)]
+pub struct ValidationRules {
+    pub negative: RuleAction,
+    pub implausible_total: RuleAction,
+    pub max_daily_mm: f64,
+    pub duplicate: RuleAction,
+    pub out_of_range: RuleAction,
+}
+
+impl ValidationRules {
+    pub fn action(&self, rule: Rule) -> RuleAction {
+        match rule {
+            Rule::Negative => self.negative,
+            Rule::ImplausibleTotal => self.implausible_total,
+            Rule::Duplicate => self.duplicate,
+            Rule::OutOfRange => self.out_of_range,
+        }
+    }
+}
+
+/// Outcome of validating one run's rows, included in the run summary.
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub rows_checked: usize,
+    pub rows_rejected: usize,
+    pub rows_flagged: usize,
+    /// Rules with at least one violation, by name.
+    pub rules: BTreeMap<&'static str, RuleResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleResult {
+    pub action: RuleAction,
+    pub violations: usize,
+    /// The first few offending rows as `station/date=value`.
+    pub examples: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Rules set to `fail` that were violated.
+    pub fn failed_rules(&self) -> Vec<&'static str> {
+        self.rules
+            .iter()
+            .filter(|(_, r)| r.action == RuleAction::Fail)
+            .map(|(&name, _)| name)
+            .collect()
+    }
+
+    fn record(&mut self, rule: Rule, action: RuleAction, row: &PrecipitationRow) {
+        let result = self.rules.entry(rule.name()).or_insert(RuleResult {
+            action,
+            violations: 0,
+            examples: Vec::new(),
+        });
+        result.violations += 1;
+        if result.examples.len() < MAX_EXAMPLES {
+            let value = row
+                .precipitation_mm
+                .map_or("null".to_string(), |v| v.to_string());
+            result
+                .examples
+                .push(format!("{}/{}={value}", row.station_id, row.reference_time));
+        }
+    }
+}
+
+/// Check `rows` against `rules` and apply each violated rule's action.
+///
+/// Returns the rows to write (rejected rows removed, flagged rows marked) and the
+/// report. Rows broken by a `fail` rule are kept; the caller fails the run when
+/// [`ValidationReport::failed_rules`] is non-empty. Duplicates are checked last, so
+/// a rejected row never shadows a later valid one for the same station and date.
+pub fn validate(
+    rows: Vec<PrecipitationRow>,
+    rules: &ValidationRules,
+    from: &str,
+    to: &str,
+) -> (Vec<PrecipitationRow>, ValidationReport) {
+    let mut report = ValidationReport {
+        rows_checked: rows.len(),
+        ..ValidationReport::default()
+    };
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let mut kept = Vec::with_capacity(rows.len());
+
+    for mut row in rows {
+        let value = row.precipitation_mm;
+        let date = row.reference_time.as_str();
+        let violated: Vec<Rule> = Rule::ALL
+            .into_iter()
+            .filter(|rule| match rule {
+                Rule::Negative => value.is_some_and(|v| v < 0.0),
+                Rule::ImplausibleTotal => value.is_some_and(|v| v > rules.max_daily_mm),
+                Rule::OutOfRange => date < from || date >= to,
+                // Decided below, once the other rules have had their say
+                Rule::Duplicate => false,
+            })
+            .collect();
+
+        let mut actions: Vec<RuleAction> = violated
+            .into_iter()
+            .map(|rule| apply(rule, rules, &mut row, &mut report))
+            .collect();
+
+        if !actions.contains(&RuleAction::Reject) {
+            let key = (row.station_id.clone(), row.reference_time.clone());
+            if !seen.insert(key) {
+                actions.push(apply(Rule::Duplicate, rules, &mut row, &mut report));
+            }
+        }
+        let rejected = actions.contains(&RuleAction::Reject);
+        let flagged = actions.contains(&RuleAction::Flag);
+
+        if rejected {
+            report.rows_rejected += 1;
+        } else {
+            if flagged {
+                report.rows_flagged += 1;
+            }
+            kept.push(row);
+        }
+    }
+
+    for (name, result) in &report.rules {
+        warn!(
+            rule = name,
+            action = result.action.as_str(),
+            violations = result.violations,
+            examples = %result.examples.join(", "),
+            "Validation rule violated"
+        );
+    }
+    info!(
+        rows = report.rows_checked,
+        rejected = report.rows_rejected,
+        flagged = report.rows_flagged,
+        "Validated fetched rows"
+    );
+    (kept, report)
+}
+
+/// Record a violation of `rule` by `row` and flag the row if that's the action.
+fn apply(
+    rule: Rule,
+    rules: &ValidationRules,
+    row: &mut PrecipitationRow,
+    report: &mut ValidationReport,
+) -> RuleAction {
+    let action = rules.action(rule);
+    report.record(rule, action, row);
+    if action == RuleAction::Flag {
+        row.flags.push(rule.name());
+    }
+    action
+}